@@ -2,6 +2,7 @@ use anyhow::Result;
 
 use crate::bootstrap;
 use crate::cli::Cli;
+use crate::commands;
 use crate::interactive;
 use crate::output;
 use crate::postprocess;
@@ -11,22 +12,30 @@ use crate::{command_validation, paths};
 pub async fn run(cli: Cli) -> Result<()> {
     paths::ensure_dirs()?;
 
-    let Some(mut runtime) = bootstrap::bootstrap(&cli).await? else {
+    if let Some(command) = cli.command.as_ref() {
+        return commands::run(&cli, command).await;
+    }
+
+    let Some(runtime) = bootstrap::bootstrap(&cli).await? else {
         return Ok(());
     };
+    let bootstrap::BootstrappedRuntime { llm, mut session } = runtime;
 
-    let engine = RequestEngine::new(
+    let mut engine = RequestEngine::new(
         &cli,
-        &runtime.llm,
+        Box::new(llm),
         postprocess::default_post_processor(),
-        command_validation::default_command_validator(),
+        command_validation::default_command_validator()?,
     );
 
     if let Some(request) = cli.once.as_deref() {
-        let result = engine.generate(request, &mut runtime.session, None).await?;
+        let result = engine.generate(request, &mut session, None).await?;
         output::print_generated_result(&result, cli.explanation)?;
+        if cli.copy {
+            output::copy_command(&result.command, &result.risk, None)?;
+        }
         return Ok(());
     }
 
-    interactive::run_interactive(&cli, &engine, &mut runtime.session).await
+    interactive::run_interactive(&cli, &mut engine, &mut session).await
 }
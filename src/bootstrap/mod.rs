@@ -1,3 +1,4 @@
+mod model_check;
 mod model_list;
 mod resolver;
 mod runtime_setup;
@@ -6,6 +7,7 @@ use anyhow::Result;
 
 use crate::cli::Cli;
 use crate::llm::LlmClient;
+use crate::model::ProviderKind;
 use crate::session::{self, SessionRecord};
 
 pub struct BootstrappedRuntime {
@@ -15,15 +17,49 @@ pub struct BootstrappedRuntime {
 
 pub async fn bootstrap(cli: &Cli) -> Result<Option<BootstrappedRuntime>> {
     let resolver = resolver::default_runtime_resolver();
-    let resumed_session = match cli.resume.as_deref() {
+    let mut resumed_session = match cli.resume.as_deref() {
         Some(uuid) => Some(session::load_session(uuid)?),
         None => None,
     };
+    if cli.fork
+        && let Some(parent) = resumed_session.take()
+    {
+        let forked = parent.fork(cli.fork_at)?;
+        eprintln!(
+            "Forked session {} from {} after turn {}",
+            forked.uuid,
+            parent.uuid,
+            forked.turns.len()
+        );
+        resumed_session = Some(forked);
+    }
 
     if model_list::try_print_model_list(cli, resumed_session.as_ref(), resolver.as_ref()).await? {
         return Ok(None);
     }
 
-    let runtime = runtime_setup::prepare_runtime(cli, resumed_session, resolver.as_ref())?;
+    let runtime = runtime_setup::prepare_runtime(cli, resumed_session, resolver.as_ref()).await?;
     Ok(Some(runtime))
 }
+
+/// Resolves the provider and model the way a real run would, without requiring an API key.
+pub fn resolve_model_without_key(
+    cli: &Cli,
+    resumed_session: Option<&SessionRecord>,
+) -> Result<(ProviderKind, String)> {
+    let resolver = resolver::default_runtime_resolver();
+    let provider =
+        resolver.resolve_provider_for_model_listing(cli.model.as_deref(), resumed_session)?;
+    let model_name =
+        resolver.resolve_model_name(provider, cli.model.as_deref(), resumed_session)?;
+    Ok((provider, model_name))
+}
+
+pub async fn switch_model(
+    cli: &Cli,
+    model_arg: &str,
+    session: &mut SessionRecord,
+) -> Result<LlmClient> {
+    let resolver = resolver::default_runtime_resolver();
+    runtime_setup::switch_model(cli, model_arg, session, resolver.as_ref()).await
+}
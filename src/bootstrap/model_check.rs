@@ -0,0 +1,18 @@
+use anyhow::Result;
+
+use crate::meta;
+use crate::model::{self, ProviderKind};
+
+/// Validates an explicitly requested model against the (cached) provider model list
+/// so typos fail with suggestions before the first generation request.
+pub async fn verify_requested_model(
+    provider: ProviderKind,
+    model_name: &str,
+    api_key: &str,
+) -> Result<()> {
+    // The list is only advisory; if it cannot be fetched, the API call reports the problem.
+    let Ok(models) = meta::get_models(provider, Some(api_key)).await else {
+        return Ok(());
+    };
+    model::ensure_known_model(provider, model_name, &models)
+}
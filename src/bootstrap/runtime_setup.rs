@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 
 use crate::bootstrap::resolver::RuntimeResolver;
 use crate::cli::Cli;
@@ -8,8 +8,9 @@ use crate::model;
 use crate::session::{self, SessionRecord};
 
 use super::BootstrappedRuntime;
+use super::model_check::verify_requested_model;
 
-pub fn prepare_runtime(
+pub async fn prepare_runtime(
     cli: &Cli,
     resumed_session: Option<SessionRecord>,
     resolver: &dyn RuntimeResolver,
@@ -22,6 +23,9 @@ pub fn prepare_runtime(
     let model_name =
         resolver.resolve_model_name(provider, cli.model.as_deref(), resumed_session.as_ref())?;
     let api_key = model::resolve_key(provider, cli.key.as_deref())?;
+    if cli.model.is_some() {
+        verify_requested_model(provider, &model_name, &api_key).await?;
+    }
 
     meta::set_last_using_model(provider, &model_name)?;
 
@@ -30,7 +34,10 @@ pub fn prepare_runtime(
         resumed_session.unwrap_or_else(|| SessionRecord::new(provider, &model_name));
     active_session.provider = provider.as_str().to_string();
     active_session.model = model_name.clone();
-    session::save_session(&active_session)?;
+    match cli.session_name.as_deref() {
+        Some(name) => session::rename_session(&mut active_session, name)?,
+        None => session::save_session(&active_session)?,
+    }
     eprintln!(
         "Session UUID: {} (resume with: command-generator --resume {})",
         active_session.uuid, active_session.uuid
@@ -41,3 +48,32 @@ pub fn prepare_runtime(
         session: active_session,
     })
 }
+
+/// Resolves `model_arg` (alias, `provider:model`, provider or model name) and returns a client
+/// for it, updating the session and last-used model the same way startup does.
+pub async fn switch_model(
+    cli: &Cli,
+    model_arg: &str,
+    session: &mut SessionRecord,
+    resolver: &dyn RuntimeResolver,
+) -> Result<LlmClient> {
+    if model_arg.trim().is_empty() {
+        return Err(anyhow!("model argument is empty"));
+    }
+    let provider = resolver.resolve_provider(Some(model_arg), cli.key.as_deref(), None)?;
+    let model_name = resolver.resolve_model_name(provider, Some(model_arg), None)?;
+    // --key was given for the startup provider; do not leak it to another provider.
+    let key_override = if model::provider_from_name(&session.provider) == Some(provider) {
+        cli.key.as_deref()
+    } else {
+        None
+    };
+    let api_key = model::resolve_key(provider, key_override)?;
+    verify_requested_model(provider, &model_name, &api_key).await?;
+
+    meta::set_last_using_model(provider, &model_name)?;
+    session.provider = provider.as_str().to_string();
+    session.model = model_name.clone();
+    session::save_session(session)?;
+    Ok(LlmClient::new(provider, api_key, model_name))
+}
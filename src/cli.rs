@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Debug, Parser)]
 #[command(
@@ -23,10 +23,22 @@ pub struct Cli {
     #[arg(short = 'c', long = "copy")]
     pub copy: bool,
 
-    /// Resume a previous session by UUID
+    /// Resume a previous session by UUID, unique UUID prefix, session name or `last`
     #[arg(short = 'r', long = "resume")]
     pub resume: Option<String>,
 
+    /// Continue the resumed session as a new forked session, leaving the original untouched
+    #[arg(long = "fork", requires = "resume")]
+    pub fork: bool,
+
+    /// Number of turns to copy into the fork (default: all)
+    #[arg(long = "fork-at", value_name = "N", requires = "fork")]
+    pub fork_at: Option<usize>,
+
+    /// Name the active session so it can be resumed with `--resume <NAME>`
+    #[arg(long = "session-name")]
+    pub session_name: Option<String>,
+
     /// Run once in non-interactive mode
     #[arg(long = "once")]
     pub once: Option<String>,
@@ -50,4 +62,116 @@ pub struct Cli {
     /// Print explanation blocks under generated command
     #[arg(short = 'e', long = "explanation")]
     pub explanation: bool,
+
+    /// Prompt template profile (loads templates from prompts/<PROFILE>/ first)
+    #[arg(long = "profile")]
+    pub profile: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Inspect prompt templates
+    Prompt {
+        #[command(subcommand)]
+        action: PromptAction,
+    },
+    /// Manage stored API keys (OS keyring or credential helper)
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+    /// List, inspect and clean up stored sessions
+    Sessions {
+        #[command(subcommand)]
+        action: SessionsAction,
+    },
+    /// Check config, meta cache and stored sessions for files that cannot be read
+    Doctor,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PromptAction {
+    /// Print the exact system and user prompt that would be sent
+    Render {
+        /// Request text to render the prompt for
+        #[arg(long = "request")]
+        request: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AuthAction {
+    /// Store an API key for a provider (reads from --key or stdin)
+    Login {
+        /// Provider name (openai, gemini, claude)
+        provider: String,
+    },
+    /// Remove the stored API key for a provider
+    Logout {
+        /// Provider name (openai, gemini, claude)
+        provider: String,
+    },
+    /// Show where each provider's API key is resolved from
+    Status,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SessionsAction {
+    /// List sessions, most recently updated first
+    List {
+        /// Maximum number of sessions to show
+        #[arg(long = "limit")]
+        limit: Option<usize>,
+    },
+    /// Show every turn of a session
+    Show {
+        /// Session UUID, unique prefix, name or `last`
+        uuid: String,
+    },
+    /// Export a session as a Markdown runbook, shell script or JSON
+    Export {
+        /// Session UUID, unique prefix, name or `last`
+        uuid: String,
+        /// Output format: md, sh or json
+        #[arg(long = "format", default_value = "md")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(short = 'o', long = "output")]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Import a session exported with `sessions export --format json`
+    Import {
+        /// Path to the exported JSON file (`-` for stdin)
+        file: std::path::PathBuf,
+    },
+    /// Search requests and generated commands across sessions
+    Search {
+        /// Text to search for (case-insensitive)
+        text: String,
+    },
+    /// Rebuild the session search index
+    Reindex,
+    /// Import JSON session files into the SQLite session store
+    Migrate,
+    /// Delete sessions by UUID
+    Delete {
+        /// Session UUIDs, unique prefixes or names
+        #[arg(required = true)]
+        uuids: Vec<String>,
+    },
+    /// Delete old sessions
+    Prune {
+        /// Delete sessions not updated within this duration (e.g. 30d, 12h, 2w)
+        #[arg(long = "older-than")]
+        older_than: Option<String>,
+        /// Always keep this many most recently updated sessions
+        #[arg(long = "keep")]
+        keep: Option<usize>,
+        /// Print what would be deleted without deleting
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+    },
 }
@@ -1,19 +1,29 @@
 use anyhow::Result;
 
-use crate::validation::{self, ValidationReport};
+use crate::config;
+use crate::validation::{self, PolicyRule, ValidationReport};
 
 pub trait CommandValidator: Send + Sync {
     fn validate(&self, command: &str) -> Result<ValidationReport>;
 }
 
-pub struct DefaultCommandValidator;
+pub struct DefaultCommandValidator {
+    policy: Vec<PolicyRule>,
+    preview_changes: bool,
+}
 
 impl CommandValidator for DefaultCommandValidator {
     fn validate(&self, command: &str) -> Result<ValidationReport> {
-        validation::validate_command(command)
+        validation::validate_command(command, &self.policy, self.preview_changes)
     }
 }
 
-pub fn default_command_validator() -> Box<dyn CommandValidator> {
-    Box::new(DefaultCommandValidator)
+/// Validator enforcing the `policy` rules from config.json, with file previews when
+/// `previewChanges` is set there.
+pub fn default_command_validator() -> Result<Box<dyn CommandValidator>> {
+    let config = config::load()?;
+    Ok(Box::new(DefaultCommandValidator {
+        policy: config.policy,
+        preview_changes: config.preview_changes,
+    }))
 }
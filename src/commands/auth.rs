@@ -0,0 +1,93 @@
+use anyhow::{Result, anyhow};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use crate::cli::{AuthAction, Cli};
+use crate::credentials;
+use crate::model::{self, ProviderKind};
+
+pub fn run(cli: &Cli, action: &AuthAction) -> Result<()> {
+    match action {
+        AuthAction::Login { provider } => login(cli, parse_provider(provider)?),
+        AuthAction::Logout { provider } => logout(parse_provider(provider)?),
+        AuthAction::Status => status(),
+    }
+}
+
+fn parse_provider(name: &str) -> Result<ProviderKind> {
+    model::provider_from_name(name).ok_or_else(|| anyhow!("unknown provider '{}'", name))
+}
+
+fn login(cli: &Cli, provider: ProviderKind) -> Result<()> {
+    let key = match cli.key.as_deref().map(str::trim) {
+        Some(key) if !key.is_empty() => key.to_string(),
+        _ => read_key(provider)?,
+    };
+    if key.is_empty() {
+        return Err(anyhow!("API key is empty"));
+    }
+    let store = credentials::active_store()?;
+    store.store(provider, &key)?;
+    println!(
+        "Stored API key for {} in {}.",
+        provider.as_str(),
+        store.describe()
+    );
+    Ok(())
+}
+
+fn logout(provider: ProviderKind) -> Result<()> {
+    let store = credentials::active_store()?;
+    store.erase(provider)?;
+    println!(
+        "Removed API key for {} from {}.",
+        provider.as_str(),
+        store.describe()
+    );
+    Ok(())
+}
+
+fn status() -> Result<()> {
+    let store = credentials::active_store()?;
+    for provider in ProviderKind::ALL {
+        let env_source = model::key_env_vars(provider)
+            .iter()
+            .find(|name| std::env::var(name).is_ok_and(|value| !value.trim().is_empty()));
+        let source = match env_source {
+            Some(name) => format!("environment ({})", name),
+            None => match store.get(provider) {
+                Ok(Some(_)) => store.describe(),
+                Ok(None) => "not configured".to_string(),
+                Err(err) => format!("error: {err}"),
+            },
+        };
+        println!("{:<8} {}", provider.as_str(), source);
+    }
+    Ok(())
+}
+
+fn read_key(provider: ProviderKind) -> Result<String> {
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        print!("API key for {}: ", provider.as_str());
+        io::stdout().flush()?;
+        set_terminal_echo(false);
+    }
+    let mut line = String::new();
+    let read = io::stdin().lock().read_line(&mut line);
+    if interactive {
+        set_terminal_echo(true);
+        println!();
+    }
+    read?;
+    Ok(line.trim().to_string())
+}
+
+fn set_terminal_echo(enabled: bool) {
+    let _ = Command::new("stty")
+        .arg(if enabled { "echo" } else { "-echo" })
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
@@ -0,0 +1,69 @@
+use anyhow::{Result, anyhow};
+
+use crate::{config, meta, paths, session};
+
+/// Reads every file `cg` keeps state in and reports the ones that would otherwise be
+/// skipped or replaced with defaults. Fails when any problem is found.
+pub fn run() -> Result<()> {
+    let mut problems = 0;
+
+    let config_path = paths::config_path();
+    match config::load() {
+        Ok(_) if config_path.exists() => println!("config:   ok ({})", config_path.display()),
+        Ok(_) => println!("config:   not present ({})", config_path.display()),
+        Err(err) => {
+            problems += 1;
+            println!("config:   unreadable: {err:#}");
+        }
+    }
+
+    match meta::check_cache() {
+        Ok(Some((path, version))) if version < meta::cache_schema_version() => println!(
+            "meta:     ok ({}, schema {} -> {} on next write)",
+            path.display(),
+            version,
+            meta::cache_schema_version()
+        ),
+        Ok(Some((path, _))) => println!("meta:     ok ({})", path.display()),
+        Ok(None) => println!("meta:     not present"),
+        Err(err) => {
+            problems += 1;
+            println!("meta:     unreadable: {err:#}");
+        }
+    }
+
+    match session::check_store() {
+        Ok((location, check)) => {
+            println!(
+                "sessions: {} readable, {} unreadable ({})",
+                check.readable,
+                check.unreadable.len(),
+                location
+            );
+            if !check.outdated.is_empty() {
+                println!(
+                    "  {} session(s) use an older schema and are upgraded on load (schema {})",
+                    check.outdated.len(),
+                    session::SESSION_SCHEMA_VERSION
+                );
+            }
+            for (location, reason) in &check.unreadable {
+                println!("  unreadable: {}: {}", location, reason);
+            }
+            for problem in &check.index_problems {
+                println!("  index: {}", problem);
+            }
+            problems += check.unreadable.len() + check.index_problems.len();
+        }
+        Err(err) => {
+            problems += 1;
+            println!("sessions: cannot open store: {err:#}");
+        }
+    }
+
+    if problems > 0 {
+        return Err(anyhow!("found {} problem(s)", problems));
+    }
+    println!("No problems found.");
+    Ok(())
+}
@@ -0,0 +1,17 @@
+mod auth;
+mod doctor;
+mod prompt;
+mod sessions;
+
+use anyhow::Result;
+
+use crate::cli::{Cli, Command};
+
+pub async fn run(cli: &Cli, command: &Command) -> Result<()> {
+    match command {
+        Command::Prompt { action } => prompt::run(cli, action),
+        Command::Auth { action } => auth::run(cli, action),
+        Command::Sessions { action } => sessions::run(action),
+        Command::Doctor => doctor::run(),
+    }
+}
@@ -0,0 +1,32 @@
+use anyhow::Result;
+
+use crate::bootstrap;
+use crate::cli::{Cli, PromptAction};
+use crate::request_engine::prompt_context::PromptStaticContext;
+use crate::session::{self, SessionRecord};
+
+pub fn run(cli: &Cli, action: &PromptAction) -> Result<()> {
+    match action {
+        PromptAction::Render { request } => render(cli, request),
+    }
+}
+
+fn render(cli: &Cli, request: &str) -> Result<()> {
+    let resumed_session = match cli.resume.as_deref() {
+        Some(uuid) => Some(session::load_session(uuid)?),
+        None => None,
+    };
+    let (provider, model_name) =
+        bootstrap::resolve_model_without_key(cli, resumed_session.as_ref())?;
+    let session = resumed_session.unwrap_or_else(|| SessionRecord::new(provider, &model_name));
+
+    let context = PromptStaticContext::new(cli, &model_name, request, &session)?;
+    let rendered = context.render(&session.uuid, Vec::new(), None, cli.explanation)?;
+    let templates = context.templates();
+
+    println!("=== system prompt ({}) ===", templates.system_source);
+    println!("{}", rendered.system);
+    println!("=== user prompt ({}) ===", templates.user_source);
+    println!("{}", rendered.user);
+    Ok(())
+}
@@ -0,0 +1,308 @@
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::cli::SessionsAction;
+use crate::session::{self, ExportFormat, SessionRecord, SessionStoreKind};
+use crate::{config, paths};
+
+const PREVIEW_WIDTH: usize = 48;
+
+pub fn run(action: &SessionsAction) -> Result<()> {
+    match action {
+        SessionsAction::List { limit } => list(*limit),
+        SessionsAction::Show { uuid } => show(uuid),
+        SessionsAction::Export {
+            uuid,
+            format,
+            output,
+        } => export(uuid, format, output.as_deref()),
+        SessionsAction::Import { file } => import(file),
+        SessionsAction::Search { text } => search(text),
+        SessionsAction::Reindex => reindex(),
+        SessionsAction::Migrate => migrate(),
+        SessionsAction::Delete { uuids } => delete(uuids),
+        SessionsAction::Prune {
+            older_than,
+            keep,
+            dry_run,
+        } => prune(older_than.as_deref(), *keep, *dry_run),
+    }
+}
+
+fn list(limit: Option<usize>) -> Result<()> {
+    let sessions = session::list_sessions()?;
+    if sessions.is_empty() {
+        println!("No sessions found.");
+        return Ok(());
+    }
+    println!(
+        "{:<36}  {:<16}  {:<16}  {:<16}  {:>5}  {:<24}  FIRST REQUEST",
+        "UUID", "NAME", "CREATED", "UPDATED", "TURNS", "MODEL"
+    );
+    for record in sessions.iter().take(limit.unwrap_or(usize::MAX)) {
+        let first = record
+            .turns
+            .first()
+            .map(|turn| preview(&turn.user_input))
+            .unwrap_or_default();
+        println!(
+            "{:<36}  {:<16}  {:<16}  {:<16}  {:>5}  {:<24}  {}",
+            record.uuid,
+            record.name.as_deref().unwrap_or("-"),
+            session::format_timestamp(record.created_at),
+            session::format_timestamp(record.updated_at),
+            record.turns.len(),
+            format!("{}:{}", record.provider, record.model),
+            first
+        );
+    }
+    Ok(())
+}
+
+fn show(uuid: &str) -> Result<()> {
+    let record = session::load_session(uuid)?;
+    println!("uuid:    {}", record.uuid);
+    if let Some(name) = &record.name {
+        println!("name:    {}", name);
+    }
+    println!("model:   {}:{}", record.provider, record.model);
+    println!("created: {}", session::format_timestamp(record.created_at));
+    println!("updated: {}", session::format_timestamp(record.updated_at));
+    println!("turns:   {}", record.turns.len());
+    if let Some(parent) = &record.parent {
+        println!(
+            "parent:  {} (after turn {})",
+            parent.uuid, parent.turn_index
+        );
+    }
+    for (index, turn) in record.turns.iter().enumerate() {
+        println!();
+        println!(
+            "[{}] {}",
+            index + 1,
+            session::format_timestamp(turn.timestamp)
+        );
+        if let Some(environment) = &turn.environment {
+            if let Some(cwd) = &environment.cwd {
+                println!("in {}", cwd);
+            }
+            if let Some(branch) = &environment.git_branch {
+                println!("on git branch {}", branch);
+            }
+        }
+        println!("> {}", turn.user_input);
+        println!("{}", turn.command);
+        if !turn.reason.is_empty() {
+            println!("# {}", turn.reason);
+        }
+    }
+    Ok(())
+}
+
+fn export(selector: &str, format: &str, output: Option<&Path>) -> Result<()> {
+    let format = ExportFormat::from_name(format)?;
+    let record = session::load_session(selector)?;
+    let rendered = session::export_session(&record, format)?;
+    match output {
+        Some(path) => {
+            fs::write(path, rendered)
+                .with_context(|| format!("failed to write export: {}", path.display()))?;
+            eprintln!("Exported session {} to {}.", record.uuid, path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+fn import(file: &Path) -> Result<()> {
+    let content = if file == Path::new("-") {
+        io::read_to_string(io::stdin()).context("failed to read session from stdin")?
+    } else {
+        fs::read_to_string(file)
+            .with_context(|| format!("failed to read import file: {}", file.display()))?
+    };
+    let imported = session::import_session(&content)?;
+    if imported.remapped() {
+        println!(
+            "Session {} already exists here; imported as {}.",
+            imported.original_uuid, imported.uuid
+        );
+    } else {
+        println!("Imported session {}.", imported.uuid);
+    }
+    if let Some(name) = &imported.dropped_name {
+        println!(
+            "The name '{}' is already in use, so the imported session has no name.",
+            name
+        );
+    }
+    println!("Resume with: command-generator --resume {}", imported.uuid);
+    Ok(())
+}
+
+fn search(text: &str) -> Result<()> {
+    if text.trim().is_empty() {
+        return Err(anyhow!("search text is empty"));
+    }
+    let matches = session::search_turns(text)?;
+    if matches.is_empty() {
+        println!("No matches for '{}'.", text.trim());
+    }
+    for item in matches {
+        println!(
+            "{} #{} ({})",
+            item.session,
+            item.turn + 1,
+            session::format_timestamp(item.timestamp)
+        );
+        if let Some(cwd) = &item.cwd {
+            println!("  in {}", cwd);
+        }
+        println!("  > {}", item.user_input);
+        println!("  {}", item.command);
+    }
+    Ok(())
+}
+
+fn reindex() -> Result<()> {
+    let count = session::rebuild_index()?;
+    println!("Indexed {} turn(s).", count);
+    Ok(())
+}
+
+fn migrate() -> Result<()> {
+    let (count, location) = session::migrate_json_to_sqlite()?;
+    println!("Imported {} session(s) into {}.", count, location);
+    if config::load()?.session_store != SessionStoreKind::Sqlite {
+        println!(
+            "Set \"sessionStore\": \"sqlite\" in {} to use it.",
+            paths::config_path().display()
+        );
+    }
+    Ok(())
+}
+
+fn delete(uuids: &[String]) -> Result<()> {
+    for selector in uuids {
+        let record = session::load_session(selector)?;
+        session::delete_session(&record.uuid)?;
+        println!("Deleted session {}.", record.uuid);
+    }
+    Ok(())
+}
+
+fn prune(older_than: Option<&str>, keep: Option<usize>, dry_run: bool) -> Result<()> {
+    if older_than.is_none() && keep.is_none() {
+        return Err(anyhow!("prune needs --older-than and/or --keep"));
+    }
+    let max_age = older_than.map(parse_duration).transpose()?;
+    let sessions = session::list_sessions()?;
+    let targets = select_prunable(&sessions, max_age, keep, session::now_unix());
+    for record in &targets {
+        if dry_run {
+            println!(
+                "Would delete {} (updated {})",
+                record.uuid,
+                session::format_timestamp(record.updated_at)
+            );
+        } else {
+            session::delete_session(&record.uuid)?;
+        }
+    }
+    if !dry_run {
+        println!("Deleted {} session(s).", targets.len());
+    }
+    Ok(())
+}
+
+/// Picks sessions to delete from `sessions` (sorted newest first). The newest `keep`
+/// sessions are always retained; with `max_age`, only sessions older than it are removed.
+fn select_prunable(
+    sessions: &[SessionRecord],
+    max_age: Option<i64>,
+    keep: Option<usize>,
+    now: i64,
+) -> Vec<&SessionRecord> {
+    sessions
+        .iter()
+        .skip(keep.unwrap_or(0))
+        .filter(|record| max_age.is_none_or(|age| now - record.updated_at > age))
+        .collect()
+}
+
+/// Parses durations like `30d`, `12h`, `2w`, `45m` or `90s` into seconds.
+fn parse_duration(value: &str) -> Result<i64> {
+    let value = value.trim();
+    let split = value
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (digits, unit) = value.split_at(split);
+    let amount: i64 = digits
+        .parse()
+        .map_err(|_| anyhow!("invalid duration '{}' (expected e.g. 30d, 12h, 2w)", value))?;
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" | "" => 86_400,
+        "w" => 7 * 86_400,
+        _ => {
+            return Err(anyhow!(
+                "invalid duration unit '{}' (use s, m, h, d or w)",
+                unit
+            ));
+        }
+    };
+    amount
+        .checked_mul(scale)
+        .ok_or_else(|| anyhow!("duration '{}' is too large", value))
+}
+
+fn preview(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() <= PREVIEW_WIDTH {
+        return line.to_string();
+    }
+    let truncated: String = line.chars().take(PREVIEW_WIDTH - 3).collect();
+    format!("{}...", truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ProviderKind;
+
+    fn record(updated_at: i64) -> SessionRecord {
+        let mut record = SessionRecord::new(ProviderKind::OpenAI, "gpt-5.2");
+        record.updated_at = updated_at;
+        record
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("30d").unwrap(), 30 * 86_400);
+        assert_eq!(parse_duration("12h").unwrap(), 12 * 3_600);
+        assert_eq!(parse_duration("2w").unwrap(), 14 * 86_400);
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("3y").is_err());
+        assert!(parse_duration("99999999999999999d").is_err());
+    }
+
+    #[test]
+    fn prune_keeps_newest_sessions() {
+        let now = 100 * 86_400;
+        let sessions = vec![
+            record(now),
+            record(now - 40 * 86_400),
+            record(now - 50 * 86_400),
+        ];
+        let by_age = select_prunable(&sessions, Some(30 * 86_400), None, now);
+        assert_eq!(by_age.len(), 2);
+        let by_age_and_keep = select_prunable(&sessions, Some(30 * 86_400), Some(2), now);
+        assert_eq!(by_age_and_keep.len(), 1);
+        assert_eq!(by_age_and_keep[0].updated_at, now - 50 * 86_400);
+        assert_eq!(select_prunable(&sessions, None, Some(1), now).len(), 2);
+    }
+}
@@ -0,0 +1,42 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::paths;
+use crate::session::SessionStoreKind;
+use crate::validation::PolicyRule;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+    /// Command invoked as `<helper> get|store|erase` to manage API keys (git-style).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_helper: Option<String>,
+    /// User-defined model names such as `fast` -> `gemini:gemini-2.5-flash`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub model_aliases: BTreeMap<String, String>,
+    /// Session storage backend: `json` (one file per session) or `sqlite`.
+    #[serde(default)]
+    pub session_store: SessionStoreKind,
+    /// Team rules every generated command must satisfy; violations are sent back to the
+    /// model so it regenerates the command.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policy: Vec<PolicyRule>,
+    /// Dry-run commands that change files in the sandbox and show what they touched.
+    /// Off by default, since the preview really runs them.
+    #[serde(default)]
+    pub preview_changes: bool,
+}
+
+pub fn load() -> Result<Config> {
+    let path = paths::config_path();
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read config: {}", path.display()))?;
+    let config: Config = serde_json::from_str(&content)
+        .with_context(|| format!("failed to parse config JSON: {}", path.display()))?;
+    Ok(config)
+}
@@ -0,0 +1,94 @@
+use anyhow::{Context, Result, anyhow};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::model::ProviderKind;
+
+pub(super) fn get(command: &str, provider: ProviderKind) -> Result<Option<String>> {
+    let output = run_helper(command, "get", &request_body(provider, None))?;
+    Ok(parse_key(&output))
+}
+
+pub(super) fn store(command: &str, provider: ProviderKind, key: &str) -> Result<()> {
+    run_helper(command, "store", &request_body(provider, Some(key)))?;
+    Ok(())
+}
+
+pub(super) fn erase(command: &str, provider: ProviderKind) -> Result<()> {
+    run_helper(command, "erase", &request_body(provider, None))?;
+    Ok(())
+}
+
+/// Builds the `key=value` request written to the helper's stdin, terminated by a blank line.
+fn request_body(provider: ProviderKind, key: Option<&str>) -> String {
+    let mut body = format!("provider={}\n", provider.as_str());
+    if let Some(key) = key {
+        body.push_str(&format!("key={}\n", key));
+    }
+    body.push('\n');
+    body
+}
+
+fn parse_key(output: &str) -> Option<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .find(|(name, _)| name.trim() == "key")
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn run_helper(command: &str, action: &str, input: &str) -> Result<String> {
+    let words = shell_words::split(command)
+        .with_context(|| format!("failed to parse credential helper command: {}", command))?;
+    let Some((program, args)) = words.split_first() else {
+        return Err(anyhow!("credential helper command is empty"));
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .arg(action)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("failed to run credential helper '{}'", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "credential helper '{}' failed on '{}' ({})",
+            program,
+            action,
+            output.status
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_request_body() {
+        assert_eq!(
+            request_body(ProviderKind::Gemini, Some("abc")),
+            "provider=gemini\nkey=abc\n\n"
+        );
+        assert_eq!(
+            request_body(ProviderKind::Claude, None),
+            "provider=claude\n\n"
+        );
+    }
+
+    #[test]
+    fn parses_key_from_helper_output() {
+        assert_eq!(
+            parse_key("provider=openai\nkey=sk-123\n").as_deref(),
+            Some("sk-123")
+        );
+        assert_eq!(parse_key("provider=openai\n"), None);
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::{Result, anyhow};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::model::ProviderKind;
+
+const SERVICE: &str = "command-generator";
+
+pub(super) fn describe() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "macOS keychain"
+    } else {
+        "Secret Service keyring"
+    }
+}
+
+pub(super) fn get(provider: ProviderKind) -> Result<Option<String>> {
+    let output = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args([
+                "find-generic-password",
+                "-s",
+                SERVICE,
+                "-a",
+                provider.as_str(),
+                "-w",
+            ])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+    } else {
+        Command::new("secret-tool")
+            .args(["lookup", "service", SERVICE, "provider", provider.as_str()])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+    };
+    // A missing keyring tool simply means no stored key.
+    let Ok(output) = output else {
+        return Ok(None);
+    };
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let key = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(Some(key).filter(|value| !value.is_empty()))
+}
+
+pub(super) fn store(provider: ProviderKind, key: &str) -> Result<()> {
+    if cfg!(target_os = "macos") {
+        // A trailing `-w` without a value makes `security` prompt for the password (and
+        // its confirmation) on stdin, which keeps the key out of the process list.
+        let mut child = Command::new("security")
+            .args([
+                "add-generic-password",
+                "-U",
+                "-s",
+                SERVICE,
+                "-a",
+                provider.as_str(),
+                "-w",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|err| anyhow!("failed to run 'security': {err}"))?;
+        if let Some(stdin) = child.stdin.as_mut() {
+            stdin.write_all(format!("{key}\n{key}\n").as_bytes())?;
+        }
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(anyhow!("'security add-generic-password' failed"));
+        }
+        return Ok(());
+    }
+
+    let label = format!("{} ({})", SERVICE, provider.as_str());
+    let mut child = Command::new("secret-tool")
+        .args([
+            "store",
+            "--label",
+            &label,
+            "service",
+            SERVICE,
+            "provider",
+            provider.as_str(),
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|err| anyhow!("failed to run 'secret-tool' (install libsecret-tools): {err}"))?;
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin.write_all(key.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow!("'secret-tool store' failed"));
+    }
+    Ok(())
+}
+
+pub(super) fn erase(provider: ProviderKind) -> Result<()> {
+    let status = if cfg!(target_os = "macos") {
+        Command::new("security")
+            .args([
+                "delete-generic-password",
+                "-s",
+                SERVICE,
+                "-a",
+                provider.as_str(),
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|err| anyhow!("failed to run 'security': {err}"))?
+    } else {
+        Command::new("secret-tool")
+            .args(["clear", "service", SERVICE, "provider", provider.as_str()])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|err| {
+                anyhow!("failed to run 'secret-tool' (install libsecret-tools): {err}")
+            })?
+    };
+    if !status.success() {
+        return Err(anyhow!(
+            "no stored key for provider '{}' in {}",
+            provider.as_str(),
+            describe()
+        ));
+    }
+    Ok(())
+}
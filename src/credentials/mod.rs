@@ -0,0 +1,108 @@
+mod helper;
+mod keyring;
+
+use anyhow::Result;
+use std::sync::Mutex;
+
+use crate::config;
+use crate::model::ProviderKind;
+
+pub enum CredentialStore {
+    Helper(String),
+    Keyring,
+}
+
+impl CredentialStore {
+    pub fn describe(&self) -> String {
+        match self {
+            CredentialStore::Helper(command) => format!("credential helper '{}'", command),
+            CredentialStore::Keyring => keyring::describe().to_string(),
+        }
+    }
+
+    pub fn get(&self, provider: ProviderKind) -> Result<Option<String>> {
+        match self {
+            CredentialStore::Helper(command) => helper::get(command, provider),
+            CredentialStore::Keyring => keyring::get(provider),
+        }
+    }
+
+    pub fn store(&self, provider: ProviderKind, key: &str) -> Result<()> {
+        match self {
+            CredentialStore::Helper(command) => helper::store(command, provider, key),
+            CredentialStore::Keyring => keyring::store(provider, key),
+        }
+    }
+
+    pub fn erase(&self, provider: ProviderKind) -> Result<()> {
+        match self {
+            CredentialStore::Helper(command) => helper::erase(command, provider),
+            CredentialStore::Keyring => keyring::erase(provider),
+        }
+    }
+}
+
+/// Returns the configured credential helper, or the OS keyring when none is set.
+pub fn active_store() -> Result<CredentialStore> {
+    let config = config::load()?;
+    let helper = config
+        .credential_helper
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    Ok(match helper {
+        Some(command) => CredentialStore::Helper(command),
+        None => CredentialStore::Keyring,
+    })
+}
+
+/// Looks up the stored key for `provider`, at most once per process: provider selection
+/// and key resolution both ask, and each lookup may spawn a credential helper.
+pub fn lookup_key(provider: ProviderKind) -> Result<Option<String>> {
+    static LOOKED_UP: Mutex<Vec<(ProviderKind, Option<String>)>> = Mutex::new(Vec::new());
+    let mut looked_up = LOOKED_UP.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some((_, key)) = looked_up.iter().find(|(kind, _)| *kind == provider) {
+        return Ok(key.clone());
+    }
+    // The keyring stays a fallback behind a configured helper, so a broken helper does
+    // not hide a key saved there.
+    let mut stores = vec![active_store()?];
+    if matches!(stores[0], CredentialStore::Helper(_)) {
+        stores.push(CredentialStore::Keyring);
+    }
+    let key = first_key(&stores, provider);
+    looked_up.push((provider, key.clone()));
+    Ok(key)
+}
+
+/// The first key any of `stores` yields. A failing store is reported and skipped.
+fn first_key(stores: &[CredentialStore], provider: ProviderKind) -> Option<String> {
+    stores.iter().find_map(|store| match store.get(provider) {
+        Ok(key) => key,
+        Err(err) => {
+            eprintln!(
+                "warning: failed to read API key from {}: {err:#}",
+                store.describe()
+            );
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_failing_stores() {
+        let stores = [
+            CredentialStore::Helper("/nonexistent/credential-helper".to_string()),
+            CredentialStore::Helper("sh -c 'exit 1'".to_string()),
+            CredentialStore::Helper("sh -c 'echo key=sk-fallback'".to_string()),
+        ];
+        assert_eq!(
+            first_key(&stores, ProviderKind::OpenAI).as_deref(),
+            Some("sk-fallback")
+        );
+        assert_eq!(first_key(&stores[..2], ProviderKind::OpenAI), None);
+    }
+}
@@ -0,0 +1,74 @@
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use std::rc::Rc;
+
+use super::slash::SlashCommandRegistry;
+
+pub struct InputHelper {
+    registry: Rc<SlashCommandRegistry>,
+}
+
+impl InputHelper {
+    pub fn new(registry: Rc<SlashCommandRegistry>) -> Self {
+        Self { registry }
+    }
+}
+
+impl Completer for InputHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        if !before.starts_with('/') {
+            return Ok((pos, Vec::new()));
+        }
+        match before.split_once(char::is_whitespace) {
+            None => {
+                let names = self
+                    .registry
+                    .commands()
+                    .iter()
+                    .map(|command| command.name().to_string())
+                    .collect::<Vec<_>>();
+                Ok((0, to_pairs(names, before)))
+            }
+            Some((name, rest)) => {
+                let Some(command) = self.registry.find(name) else {
+                    return Ok((pos, Vec::new()));
+                };
+                let partial = rest.trim_start();
+                let start = pos - partial.len();
+                Ok((start, to_pairs(command.complete_args(partial), partial)))
+            }
+        }
+    }
+}
+
+impl Hinter for InputHelper {
+    type Hint = String;
+}
+
+impl Highlighter for InputHelper {}
+
+impl Validator for InputHelper {}
+
+impl Helper for InputHelper {}
+
+fn to_pairs(values: Vec<String>, partial: &str) -> Vec<Pair> {
+    values
+        .into_iter()
+        .filter(|value| value.starts_with(partial))
+        .map(|value| Pair {
+            display: value.clone(),
+            replacement: value,
+        })
+        .collect()
+}
@@ -1,3 +1,5 @@
+mod completion;
+mod slash;
 mod stdio_loop;
 mod tty_loop;
 
@@ -5,17 +7,23 @@ use anyhow::Result;
 use std::future::Future;
 use std::io::{self, IsTerminal};
 use std::pin::Pin;
+use std::rc::Rc;
 
 use crate::cli::Cli;
 use crate::request_engine::RequestEngine;
 use crate::session::SessionRecord;
 
+pub use slash::{
+    SlashCommand, SlashCommandRegistry, SlashContext, SlashFuture, SlashOutcome, parse_slash_input,
+};
+
 trait InteractiveBackend {
-    fn run<'a>(
+    fn run<'a, 'e: 'a>(
         &'a self,
         cli: &'a Cli,
-        engine: &'a RequestEngine<'a>,
+        engine: &'a mut RequestEngine<'e>,
         session: &'a mut SessionRecord,
+        registry: Rc<SlashCommandRegistry>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>>;
 }
 
@@ -23,36 +31,39 @@ struct TtyBackend;
 struct StdioBackend;
 
 impl InteractiveBackend for TtyBackend {
-    fn run<'a>(
+    fn run<'a, 'e: 'a>(
         &'a self,
         cli: &'a Cli,
-        engine: &'a RequestEngine<'a>,
+        engine: &'a mut RequestEngine<'e>,
         session: &'a mut SessionRecord,
+        registry: Rc<SlashCommandRegistry>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
-        Box::pin(tty_loop::run(cli, engine, session))
+        Box::pin(tty_loop::run(cli, engine, session, registry))
     }
 }
 
 impl InteractiveBackend for StdioBackend {
-    fn run<'a>(
+    fn run<'a, 'e: 'a>(
         &'a self,
         cli: &'a Cli,
-        engine: &'a RequestEngine<'a>,
+        engine: &'a mut RequestEngine<'e>,
         session: &'a mut SessionRecord,
+        registry: Rc<SlashCommandRegistry>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
-        Box::pin(stdio_loop::run(cli, engine, session))
+        Box::pin(stdio_loop::run(cli, engine, session, registry))
     }
 }
 
 pub async fn run_interactive(
     cli: &Cli,
-    engine: &RequestEngine<'_>,
+    engine: &mut RequestEngine<'_>,
     session: &mut SessionRecord,
 ) -> Result<()> {
     if cli.resume.is_some() {
         print_resumed_context(session, cli.context_turns);
     }
-    println!("Interactive mode. Type exit to finish.");
+    println!("Interactive mode. Type /help for commands, exit to finish.");
+    let registry = Rc::new(SlashCommandRegistry::with_default_commands());
 
     let backend: &dyn InteractiveBackend =
         if io::stdin().is_terminal() && io::stdout().is_terminal() {
@@ -60,11 +71,11 @@ pub async fn run_interactive(
         } else {
             &StdioBackend
         };
-    backend.run(cli, engine, session).await
+    backend.run(cli, engine, session, registry).await
 }
 
 pub fn is_exit_command(input: &str) -> bool {
-    matches!(input, "exit" | "quit" | "/exit" | "/quit")
+    matches!(input, "exit" | "quit")
 }
 
 fn print_resumed_context(session: &SessionRecord, limit: usize) {
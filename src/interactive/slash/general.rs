@@ -0,0 +1,180 @@
+use anyhow::anyhow;
+use std::io::{self, Write};
+
+use super::{SlashCommand, SlashContext, SlashFuture, SlashOutcome};
+use crate::output;
+use crate::prompter::StdioPrompter;
+use crate::validation;
+
+pub struct HelpCommand;
+
+impl SlashCommand for HelpCommand {
+    fn name(&self) -> &'static str {
+        "/help"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show available commands"
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, _args: &'a str) -> SlashFuture<'a> {
+        Box::pin(async move {
+            for command in ctx.registry.commands() {
+                let signature = if command.usage().is_empty() {
+                    command.name().to_string()
+                } else {
+                    format!("{} {}", command.name(), command.usage())
+                };
+                println!("{:<28} {}", signature, command.description());
+            }
+            Ok(SlashOutcome::Continue)
+        })
+    }
+}
+
+pub struct CopyCommand;
+
+impl SlashCommand for CopyCommand {
+    fn name(&self) -> &'static str {
+        "/copy"
+    }
+
+    fn description(&self) -> &'static str {
+        "Copy the last generated command to the clipboard"
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, _args: &'a str) -> SlashFuture<'a> {
+        Box::pin(async move {
+            let turn = ctx
+                .session
+                .turns
+                .last()
+                .ok_or_else(|| anyhow!("no command generated in this session yet"))?;
+            let risk = turn
+                .validation
+                .risk
+                .clone()
+                .unwrap_or_else(|| validation::assess_risk(&turn.command));
+            if output::copy_command(&turn.command, &risk, Some(&mut StdioPrompter::new()))? {
+                println!("Copied: {}", turn.command);
+            }
+            Ok(SlashOutcome::Continue)
+        })
+    }
+}
+
+pub struct RetryCommand;
+
+impl SlashCommand for RetryCommand {
+    fn name(&self) -> &'static str {
+        "/retry"
+    }
+
+    fn description(&self) -> &'static str {
+        "Run the last request again"
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, _args: &'a str) -> SlashFuture<'a> {
+        Box::pin(async move {
+            let request = ctx
+                .last_request
+                .map(str::to_string)
+                .or_else(|| ctx.session.turns.last().map(|turn| turn.user_input.clone()))
+                .ok_or_else(|| anyhow!("no previous request to retry"))?;
+            Ok(SlashOutcome::Submit(request))
+        })
+    }
+}
+
+pub struct ExplainCommand;
+
+impl SlashCommand for ExplainCommand {
+    fn name(&self) -> &'static str {
+        "/explain"
+    }
+
+    fn usage(&self) -> &'static str {
+        "on|off"
+    }
+
+    fn description(&self) -> &'static str {
+        "Toggle explanation blocks under generated commands"
+    }
+
+    fn complete_args(&self, partial: &str) -> Vec<String> {
+        ["on", "off"]
+            .iter()
+            .filter(|value| value.starts_with(partial))
+            .map(|value| value.to_string())
+            .collect()
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, args: &'a str) -> SlashFuture<'a> {
+        Box::pin(async move {
+            let enabled = match args {
+                "on" => true,
+                "off" => false,
+                "" => !ctx.engine.explanation_mode(),
+                other => return Err(anyhow!("expected 'on' or 'off', got '{}'", other)),
+            };
+            ctx.engine.set_explanation_mode(enabled);
+            println!(
+                "Explanation mode {}.",
+                if enabled { "enabled" } else { "disabled" }
+            );
+            Ok(SlashOutcome::Continue)
+        })
+    }
+}
+
+pub struct ClearCommand;
+
+impl SlashCommand for ClearCommand {
+    fn name(&self) -> &'static str {
+        "/clear"
+    }
+
+    fn description(&self) -> &'static str {
+        "Clear the screen"
+    }
+
+    fn execute<'a>(
+        &'a self,
+        _ctx: &'a mut SlashContext<'_, '_>,
+        _args: &'a str,
+    ) -> SlashFuture<'a> {
+        Box::pin(async move {
+            print!("\x1b[2J\x1b[H");
+            io::stdout().flush()?;
+            Ok(SlashOutcome::Continue)
+        })
+    }
+}
+
+pub struct ExitCommand {
+    name: &'static str,
+}
+
+impl ExitCommand {
+    pub fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+impl SlashCommand for ExitCommand {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        "Leave interactive mode"
+    }
+
+    fn execute<'a>(
+        &'a self,
+        _ctx: &'a mut SlashContext<'_, '_>,
+        _args: &'a str,
+    ) -> SlashFuture<'a> {
+        Box::pin(async move { Ok(SlashOutcome::Exit) })
+    }
+}
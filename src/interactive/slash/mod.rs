@@ -0,0 +1,185 @@
+mod general;
+mod model;
+mod session;
+
+use anyhow::Result;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::cli::Cli;
+use crate::request_engine::RequestEngine;
+use crate::session::SessionRecord;
+
+pub type SlashFuture<'a> = Pin<Box<dyn Future<Output = Result<SlashOutcome>> + 'a>>;
+
+pub enum SlashOutcome {
+    Continue,
+    Exit,
+    /// Run the given text as a normal generation request.
+    Submit(String),
+}
+
+pub struct SlashContext<'s, 'e> {
+    pub cli: &'s Cli,
+    pub engine: &'s mut RequestEngine<'e>,
+    pub session: &'s mut SessionRecord,
+    pub last_request: Option<&'s str>,
+    pub registry: &'s SlashCommandRegistry,
+}
+
+pub trait SlashCommand {
+    /// Command name including the leading slash, e.g. `/help`.
+    fn name(&self) -> &'static str;
+
+    fn usage(&self) -> &'static str {
+        ""
+    }
+
+    fn description(&self) -> &'static str;
+
+    fn complete_args(&self, _partial: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, args: &'a str) -> SlashFuture<'a>;
+}
+
+pub struct SlashCommandRegistry {
+    commands: Vec<Box<dyn SlashCommand>>,
+}
+
+impl SlashCommandRegistry {
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn with_default_commands() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(general::HelpCommand));
+        registry.register(Box::new(general::CopyCommand));
+        registry.register(Box::new(general::RetryCommand));
+        registry.register(Box::new(session::UndoCommand));
+        registry.register(Box::new(session::HistoryCommand));
+        registry.register(Box::new(session::SessionCommand));
+        registry.register(Box::new(session::NameCommand));
+        registry.register(Box::new(session::ForkCommand));
+        registry.register(Box::new(model::ModelCommand::new()));
+        registry.register(Box::new(general::ExplainCommand));
+        registry.register(Box::new(general::ClearCommand));
+        registry.register(Box::new(general::ExitCommand::new("/exit")));
+        registry.register(Box::new(general::ExitCommand::new("/quit")));
+        registry
+    }
+
+    /// Adds a command; a later registration with the same name replaces the earlier one.
+    pub fn register(&mut self, command: Box<dyn SlashCommand>) {
+        self.commands
+            .retain(|existing| existing.name() != command.name());
+        self.commands.push(command);
+    }
+
+    pub fn commands(&self) -> &[Box<dyn SlashCommand>] {
+        &self.commands
+    }
+
+    pub fn find(&self, name: &str) -> Option<&dyn SlashCommand> {
+        self.commands
+            .iter()
+            .find(|command| command.name() == name)
+            .map(|command| command.as_ref())
+    }
+
+    /// The registered command `input` invokes, and its arguments. Anything else, such as
+    /// a request starting with a path (`/var/log too big`), is a regular request.
+    pub fn resolve<'i>(&self, input: &'i str) -> Option<(&dyn SlashCommand, &'i str)> {
+        let (name, args) = parse_slash_input(input)?;
+        Some((self.find(name)?, args))
+    }
+
+    /// Runs `input` if it is a slash command. Returns `None` for regular requests.
+    pub async fn dispatch(
+        &self,
+        input: &str,
+        cli: &Cli,
+        engine: &mut RequestEngine<'_>,
+        session: &mut SessionRecord,
+        last_request: Option<&str>,
+    ) -> Option<Result<SlashOutcome>> {
+        let (command, args) = self.resolve(input)?;
+        let mut ctx = SlashContext {
+            cli,
+            engine,
+            session,
+            last_request,
+            registry: self,
+        };
+        Some(command.execute(&mut ctx, args).await)
+    }
+}
+
+impl Default for SlashCommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Splits `/name args` into its parts. Input whose first word contains another `/`
+/// (e.g. a path like `/tmp/foo`) is treated as a regular request; see
+/// `SlashCommandRegistry::resolve` for names that are not registered.
+pub fn parse_slash_input(input: &str) -> Option<(&str, &str)> {
+    let trimmed = input.trim();
+    if !trimmed.starts_with('/') {
+        return None;
+    }
+    let (name, args) = match trimmed.split_once(char::is_whitespace) {
+        Some((name, args)) => (name, args.trim()),
+        None => (trimmed, ""),
+    };
+    if name.len() < 2 || name[1..].contains('/') {
+        return None;
+    }
+    Some((name, args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_slash_input() {
+        assert_eq!(parse_slash_input("/help"), Some(("/help", "")));
+        assert_eq!(
+            parse_slash_input("/model  gemini:gemini-2.5-flash "),
+            Some(("/model", "gemini:gemini-2.5-flash"))
+        );
+        assert_eq!(parse_slash_input("/tmp/foo is full, clean it"), None);
+        assert_eq!(parse_slash_input("list files"), None);
+        assert_eq!(parse_slash_input("/"), None);
+    }
+
+    #[test]
+    fn passes_unregistered_names_through_as_requests() {
+        let registry = SlashCommandRegistry::with_default_commands();
+        let (command, args) = registry.resolve("/model openai").unwrap();
+        assert_eq!((command.name(), args), ("/model", "openai"));
+        assert!(registry.resolve("/tmp is full, clean it").is_none());
+        assert!(registry.resolve("/var/log too big").is_none());
+    }
+
+    #[test]
+    fn registers_default_commands_once() {
+        let registry = SlashCommandRegistry::with_default_commands();
+        for name in [
+            "/help", "/copy", "/retry", "/undo", "/history", "/session", "/name", "/fork",
+            "/model", "/explain", "/clear", "/exit", "/quit",
+        ] {
+            assert!(registry.find(name).is_some(), "missing {name}");
+        }
+        let mut registry = registry;
+        let before = registry.commands().len();
+        registry.register(Box::new(general::ClearCommand));
+        assert_eq!(registry.commands().len(), before);
+    }
+}
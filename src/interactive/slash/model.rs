@@ -0,0 +1,59 @@
+use super::{SlashCommand, SlashContext, SlashFuture, SlashOutcome};
+use crate::{bootstrap, config, meta};
+
+pub struct ModelCommand {
+    models: Vec<String>,
+}
+
+impl ModelCommand {
+    pub fn new() -> Self {
+        let mut models = meta::cached_models().unwrap_or_default();
+        if let Ok(config) = config::load() {
+            models.extend(config.model_aliases.into_keys());
+        }
+        models.sort();
+        models.dedup();
+        Self { models }
+    }
+}
+
+impl SlashCommand for ModelCommand {
+    fn name(&self) -> &'static str {
+        "/model"
+    }
+
+    fn usage(&self) -> &'static str {
+        "[provider:model]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show or switch the model for this session"
+    }
+
+    fn complete_args(&self, partial: &str) -> Vec<String> {
+        self.models
+            .iter()
+            .filter(|model| model.starts_with(partial))
+            .cloned()
+            .collect()
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, args: &'a str) -> SlashFuture<'a> {
+        Box::pin(async move {
+            if args.is_empty() {
+                println!(
+                    "Current model: {}:{}",
+                    ctx.session.provider, ctx.session.model
+                );
+                return Ok(SlashOutcome::Continue);
+            }
+            let llm = bootstrap::switch_model(ctx.cli, args, ctx.session).await?;
+            ctx.engine.replace_gateway(Box::new(llm));
+            println!(
+                "Switched model to {}:{}",
+                ctx.session.provider, ctx.session.model
+            );
+            Ok(SlashOutcome::Continue)
+        })
+    }
+}
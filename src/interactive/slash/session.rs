@@ -0,0 +1,172 @@
+use anyhow::anyhow;
+
+use super::{SlashCommand, SlashContext, SlashFuture, SlashOutcome};
+use crate::session;
+
+pub struct UndoCommand;
+
+impl SlashCommand for UndoCommand {
+    fn name(&self) -> &'static str {
+        "/undo"
+    }
+
+    fn description(&self) -> &'static str {
+        "Drop the last turn from the session context"
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, _args: &'a str) -> SlashFuture<'a> {
+        Box::pin(async move {
+            let turn = ctx
+                .session
+                .turns
+                .pop()
+                .ok_or_else(|| anyhow!("session has no turns to undo"))?;
+            session::save_session(ctx.session)?;
+            println!("Dropped: > {}", turn.user_input);
+            println!("         {}", turn.command);
+            Ok(SlashOutcome::Continue)
+        })
+    }
+}
+
+pub struct HistoryCommand;
+
+impl SlashCommand for HistoryCommand {
+    fn name(&self) -> &'static str {
+        "/history"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show all turns of this session"
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, _args: &'a str) -> SlashFuture<'a> {
+        Box::pin(async move {
+            if ctx.session.turns.is_empty() {
+                println!("No turns in this session yet.");
+            }
+            for (index, turn) in ctx.session.turns.iter().enumerate() {
+                println!("{:>3}. > {}", index + 1, turn.user_input);
+                println!("     {}", turn.command);
+            }
+            Ok(SlashOutcome::Continue)
+        })
+    }
+}
+
+pub struct SessionCommand;
+
+impl SlashCommand for SessionCommand {
+    fn name(&self) -> &'static str {
+        "/session"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show the current session details"
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, _args: &'a str) -> SlashFuture<'a> {
+        Box::pin(async move {
+            let session = &ctx.session;
+            println!("uuid:        {}", session.uuid);
+            if let Some(name) = &session.name {
+                println!("name:        {}", name);
+            }
+            println!("model:       {}:{}", session.provider, session.model);
+            println!("turns:       {}", session.turns.len());
+            if let Some(parent) = &session.parent {
+                println!(
+                    "forked from: {} (after turn {})",
+                    parent.uuid, parent.turn_index
+                );
+            }
+            println!(
+                "explanation: {}",
+                if ctx.engine.explanation_mode() {
+                    "on"
+                } else {
+                    "off"
+                }
+            );
+            println!("resume with: command-generator --resume {}", session.uuid);
+            Ok(SlashOutcome::Continue)
+        })
+    }
+}
+
+pub struct NameCommand;
+
+impl SlashCommand for NameCommand {
+    fn name(&self) -> &'static str {
+        "/name"
+    }
+
+    fn usage(&self) -> &'static str {
+        "[name]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Show or set the session name used by --resume"
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, args: &'a str) -> SlashFuture<'a> {
+        Box::pin(async move {
+            if args.is_empty() {
+                match &ctx.session.name {
+                    Some(name) => println!("Session name: {}", name),
+                    None => println!("Session has no name (set one with /name <name>)."),
+                }
+                return Ok(SlashOutcome::Continue);
+            }
+            session::rename_session(ctx.session, args)?;
+            println!(
+                "Session named '{}' (resume with: command-generator --resume {})",
+                args, args
+            );
+            Ok(SlashOutcome::Continue)
+        })
+    }
+}
+
+pub struct ForkCommand;
+
+impl SlashCommand for ForkCommand {
+    fn name(&self) -> &'static str {
+        "/fork"
+    }
+
+    fn usage(&self) -> &'static str {
+        "[turns]"
+    }
+
+    fn description(&self) -> &'static str {
+        "Continue in a new session copying the first N turns (default: all)"
+    }
+
+    fn execute<'a>(&'a self, ctx: &'a mut SlashContext<'_, '_>, args: &'a str) -> SlashFuture<'a> {
+        Box::pin(async move {
+            let turns = if args.is_empty() {
+                None
+            } else {
+                Some(
+                    args.parse::<usize>()
+                        .map_err(|_| anyhow!("expected a turn count, got '{}'", args))?,
+                )
+            };
+            let forked = ctx.session.fork(turns)?;
+            session::save_session(&forked)?;
+            println!(
+                "Forked session {} from {} after turn {}",
+                forked.uuid,
+                ctx.session.uuid,
+                forked.turns.len()
+            );
+            println!(
+                "Return to the original with: command-generator --resume {}",
+                ctx.session.uuid
+            );
+            *ctx.session = forked;
+            Ok(SlashOutcome::Continue)
+        })
+    }
+}
@@ -1,17 +1,25 @@
 use anyhow::Result;
 use std::io::{self, BufRead, Write};
+use std::rc::Rc;
 
 use crate::cli::Cli;
 use crate::interactive::is_exit_command;
+use crate::interactive::slash::{SlashCommandRegistry, SlashOutcome};
 use crate::output;
 use crate::prompter::StdioPrompter;
 use crate::request_engine::RequestEngine;
 use crate::session::SessionRecord;
 
-pub async fn run(cli: &Cli, engine: &RequestEngine<'_>, session: &mut SessionRecord) -> Result<()> {
+pub async fn run(
+    cli: &Cli,
+    engine: &mut RequestEngine<'_>,
+    session: &mut SessionRecord,
+    registry: Rc<SlashCommandRegistry>,
+) -> Result<()> {
     let stdin = io::stdin();
     let mut lock = stdin.lock();
     let mut line = String::new();
+    let mut last_request: Option<String> = None;
     loop {
         line.clear();
         print!("> ");
@@ -28,12 +36,40 @@ pub async fn run(cli: &Cli, engine: &RequestEngine<'_>, session: &mut SessionRec
             println!("Good Bye!");
             break;
         }
+        let request = match registry
+            .dispatch(input, cli, engine, session, last_request.as_deref())
+            .await
+        {
+            None => input.to_string(),
+            Some(Ok(SlashOutcome::Continue)) => continue,
+            Some(Ok(SlashOutcome::Exit)) => {
+                println!("Good Bye!");
+                break;
+            }
+            Some(Ok(SlashOutcome::Submit(request))) => request,
+            Some(Err(err)) => {
+                eprintln!("error: {err}");
+                continue;
+            }
+        };
 
         let mut prompter = StdioPrompter::new();
-        match engine.generate(input, session, Some(&mut prompter)).await {
-            Ok(result) => output::print_generated_result(&result, cli.explanation)?,
+        match engine
+            .generate(&request, session, Some(&mut prompter))
+            .await
+        {
+            Ok(result) => {
+                output::print_generated_result(&result, engine.explanation_mode())?;
+                if cli.copy
+                    && let Err(err) =
+                        output::copy_command(&result.command, &result.risk, Some(&mut prompter))
+                {
+                    eprintln!("error: {err}");
+                }
+            }
             Err(err) => eprintln!("error: {err}"),
         }
+        last_request = Some(request);
     }
     Ok(())
 }
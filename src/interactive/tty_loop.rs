@@ -1,15 +1,30 @@
 use anyhow::Result;
 use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::{CompletionType, Config, Editor};
+use std::rc::Rc;
 
 use crate::cli::Cli;
+use crate::interactive::completion::InputHelper;
 use crate::interactive::is_exit_command;
+use crate::interactive::slash::{SlashCommandRegistry, SlashOutcome};
 use crate::output;
 use crate::prompter::EditorPrompter;
 use crate::request_engine::RequestEngine;
 use crate::session::SessionRecord;
 
-pub async fn run(cli: &Cli, engine: &RequestEngine<'_>, session: &mut SessionRecord) -> Result<()> {
-    let mut editor = rustyline::DefaultEditor::new()?;
+pub async fn run(
+    cli: &Cli,
+    engine: &mut RequestEngine<'_>,
+    session: &mut SessionRecord,
+    registry: Rc<SlashCommandRegistry>,
+) -> Result<()> {
+    let editor_config = Config::builder()
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor = Editor::<InputHelper, DefaultHistory>::with_config(editor_config)?;
+    editor.set_helper(Some(InputHelper::new(Rc::clone(&registry))));
+    let mut last_request: Option<String> = None;
     loop {
         match editor.readline("> ") {
             Ok(line) => {
@@ -22,12 +37,43 @@ pub async fn run(cli: &Cli, engine: &RequestEngine<'_>, session: &mut SessionRec
                     println!("Good Bye!");
                     break;
                 }
+                let request = match registry
+                    .dispatch(input, cli, engine, session, last_request.as_deref())
+                    .await
+                {
+                    None => input.to_string(),
+                    Some(Ok(SlashOutcome::Continue)) => continue,
+                    Some(Ok(SlashOutcome::Exit)) => {
+                        println!("Good Bye!");
+                        break;
+                    }
+                    Some(Ok(SlashOutcome::Submit(request))) => request,
+                    Some(Err(err)) => {
+                        eprintln!("error: {err}");
+                        continue;
+                    }
+                };
 
                 let mut prompter = EditorPrompter::new(&mut editor);
-                match engine.generate(input, session, Some(&mut prompter)).await {
-                    Ok(result) => output::print_generated_result(&result, cli.explanation)?,
+                match engine
+                    .generate(&request, session, Some(&mut prompter))
+                    .await
+                {
+                    Ok(result) => {
+                        output::print_generated_result(&result, engine.explanation_mode())?;
+                        if cli.copy
+                            && let Err(err) = output::copy_command(
+                                &result.command,
+                                &result.risk,
+                                Some(&mut prompter),
+                            )
+                        {
+                            eprintln!("error: {err}");
+                        }
+                    }
                     Err(err) => eprintln!("error: {err}"),
                 }
+                last_request = Some(request);
             }
             Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => {
                 println!("Good Bye!");
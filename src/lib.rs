@@ -3,6 +3,9 @@ pub mod bootstrap;
 pub mod cli;
 pub mod clipboard;
 pub mod command_validation;
+pub mod commands;
+pub mod config;
+pub mod credentials;
 pub mod history;
 pub mod interactive;
 pub mod llm;
@@ -14,5 +17,9 @@ pub mod postprocess;
 pub mod prompt;
 pub mod prompter;
 pub mod request_engine;
+pub mod schema;
 pub mod session;
+pub mod shell;
+pub mod storage;
+pub mod tools;
 pub mod validation;
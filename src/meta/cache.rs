@@ -1,20 +1,63 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::fs;
+use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::paths;
+use crate::schema::Schema;
+use crate::tools::ToolInfo;
+use crate::{paths, storage};
 
 const TTL_SECONDS: i64 = 60 * 60 * 24;
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+pub(crate) const META_SCHEMA: Schema = Schema {
+    name: "meta cache",
+    version_field: "schemaVersion",
+    migrations: &[rename_last_updated_time, add_tool_inventory],
+};
+
+#[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct MetaCache {
+    #[serde(rename = "schemaVersion")]
+    pub(crate) schema_version: u32,
     #[serde(rename = "lastUsingModel")]
     pub(crate) last_using_model: Option<String>,
-    #[serde(rename = "lastFetchedModelDateTime", alias = "lastUpdatedTime")]
+    #[serde(rename = "lastFetchedModelDateTime")]
     pub(crate) last_fetched_model_datetime: Option<i64>,
-    #[serde(default)]
     pub(crate) models: Vec<String>,
+    /// Installed tool flavors and versions for the prompt; see `tools::tool_inventory`.
+    pub(crate) tools: Vec<ToolInfo>,
+}
+
+impl Default for MetaCache {
+    fn default() -> Self {
+        Self {
+            schema_version: META_SCHEMA.current_version(),
+            last_using_model: None,
+            last_fetched_model_datetime: None,
+            models: Vec::new(),
+            tools: Vec::new(),
+        }
+    }
+}
+
+/// Version 1 -> 2: the fetch time was first stored as `lastUpdatedTime`, and `models`
+/// was missing before model lists were cached.
+fn rename_last_updated_time(meta: &mut Map<String, Value>) -> Result<()> {
+    if let Some(time) = meta.remove("lastUpdatedTime") {
+        meta.entry("lastFetchedModelDateTime").or_insert(time);
+    }
+    meta.entry("models")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    Ok(())
+}
+
+/// Version 2 -> 3: the tool inventory was added.
+fn add_tool_inventory(meta: &mut Map<String, Value>) -> Result<()> {
+    meta.entry("tools")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    Ok(())
 }
 
 pub(crate) fn read_meta() -> Result<MetaCache> {
@@ -23,19 +66,29 @@ pub(crate) fn read_meta() -> Result<MetaCache> {
         return Ok(MetaCache::default());
     }
     let content = fs::read_to_string(path).with_context(|| "failed to read meta cache")?;
-    let meta: MetaCache =
-        serde_json::from_str(&content).with_context(|| "failed to parse meta cache JSON")?;
+    let (meta, _) = parse_meta(&content).with_context(|| "failed to parse meta cache JSON")?;
     Ok(meta)
 }
 
-pub(crate) fn write_meta(meta: &MetaCache) -> Result<()> {
+/// Parses meta cache JSON, upgrading it to the current schema first. Returns the cache
+/// and the schema version it was stored with.
+pub(crate) fn parse_meta(content: &str) -> Result<(MetaCache, u32)> {
+    let mut value: Value = serde_json::from_str(content)?;
+    let stored_version = META_SCHEMA.upgrade(&mut value)?;
+    Ok((serde_json::from_value(value)?, stored_version))
+}
+
+/// Re-reads the cache under an exclusive lock, applies `apply` and writes it back
+/// atomically, so concurrent `cg` processes do not drop each other's updates.
+pub(crate) fn update_meta(apply: impl FnOnce(&mut MetaCache)) -> Result<MetaCache> {
     let path = meta_path();
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).with_context(|| "failed to create cache directory")?;
-    }
-    let content = serde_json::to_string_pretty(meta)?;
-    fs::write(path, content).with_context(|| "failed to write meta cache")?;
-    Ok(())
+    let _lock = storage::lock_exclusive(&path)?;
+    let mut meta = read_meta()?;
+    apply(&mut meta);
+    let content = serde_json::to_string_pretty(&meta)?;
+    storage::write_atomic(&path, content.as_bytes())
+        .with_context(|| "failed to write meta cache")?;
+    Ok(meta)
 }
 
 pub(crate) fn models_for_provider(models: &[String], prefix: &str) -> Vec<String> {
@@ -78,6 +131,22 @@ pub(crate) fn now_unix() -> i64 {
         .unwrap_or(0)
 }
 
-fn meta_path() -> std::path::PathBuf {
+pub(crate) fn meta_path() -> PathBuf {
     paths::cache_dir().join("meta.json")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrades_legacy_last_updated_time() {
+        let (meta, stored_version) =
+            parse_meta(r#"{"lastUsingModel":"openai:gpt-5.2","lastUpdatedTime":42}"#).unwrap();
+        assert_eq!(stored_version, 1);
+        assert_eq!(meta.schema_version, META_SCHEMA.current_version());
+        assert_eq!(meta.last_fetched_model_datetime, Some(42));
+        assert!(meta.models.is_empty());
+        assert!(meta.tools.is_empty());
+    }
+}
@@ -1,9 +1,12 @@
 mod cache;
 mod fetch;
 
-use anyhow::{Result, anyhow};
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::path::PathBuf;
 
-use crate::model::ProviderKind;
+use crate::model::{ProviderKind, provider_from_name};
+use crate::tools::ToolInfo;
 
 pub fn get_last_using_model(provider: ProviderKind) -> Result<Option<String>> {
     let meta = cache::read_meta()?;
@@ -14,15 +17,58 @@ pub fn get_last_using_model(provider: ProviderKind) -> Result<Option<String>> {
     Ok(model)
 }
 
+/// Returns the provider of the most recently used model.
+pub fn get_last_using_provider() -> Result<Option<ProviderKind>> {
+    let meta = cache::read_meta()?;
+    Ok(meta
+        .last_using_model
+        .as_deref()
+        .and_then(|value| value.split_once(':'))
+        .and_then(|(provider, _)| provider_from_name(provider)))
+}
+
 pub fn set_last_using_model(provider: ProviderKind, model: &str) -> Result<()> {
-    let mut meta = cache::read_meta()?;
-    meta.last_using_model = Some(format!("{}:{}", provider.as_str(), model));
-    cache::write_meta(&meta)?;
+    let value = format!("{}:{}", provider.as_str(), model);
+    cache::update_meta(|meta| meta.last_using_model = Some(value))?;
+    Ok(())
+}
+
+/// Parses the meta cache file without falling back to defaults. Returns its path and the
+/// schema version it is stored with, or `None` when there is no cache yet.
+pub fn check_cache() -> Result<Option<(PathBuf, u32)>> {
+    let path = cache::meta_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let (_, version) = cache::parse_meta(&content)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    Ok(Some((path, version)))
+}
+
+/// Returns the schema version this build writes to the meta cache.
+pub fn cache_schema_version() -> u32 {
+    cache::META_SCHEMA.current_version()
+}
+
+/// Returns every cached `provider:model` entry without touching the network.
+pub fn cached_models() -> Result<Vec<String>> {
+    Ok(cache::read_meta()?.models)
+}
+
+/// Returns the tool inventory from the last run; see `tools::tool_inventory`.
+pub(crate) fn cached_tools() -> Result<Vec<ToolInfo>> {
+    Ok(cache::read_meta()?.tools)
+}
+
+pub(crate) fn set_cached_tools(tools: Vec<ToolInfo>) -> Result<()> {
+    cache::update_meta(|meta| meta.tools = tools)?;
     Ok(())
 }
 
 pub async fn get_models(provider: ProviderKind, key: Option<&str>) -> Result<Vec<String>> {
-    let mut meta = cache::read_meta()?;
+    let meta = cache::read_meta()?;
     let prefix = provider.as_str();
     let cached = cache::models_for_provider(&meta.models, prefix);
 
@@ -35,8 +81,9 @@ pub async fn get_models(provider: ProviderKind, key: Option<&str>) -> Result<Vec
 
     let key = key.ok_or_else(|| anyhow!("API key is required to fetch models"))?;
     let fetched = fetch::fetch_models(provider, key).await?;
-    cache::update_provider_models(&mut meta.models, prefix, &fetched);
-    meta.last_fetched_model_datetime = Some(cache::now_unix());
-    cache::write_meta(&meta)?;
+    let meta = cache::update_meta(|meta| {
+        cache::update_provider_models(&mut meta.models, prefix, &fetched);
+        meta.last_fetched_model_datetime = Some(cache::now_unix());
+    })?;
     Ok(cache::models_for_provider(&meta.models, prefix))
 }
@@ -2,4 +2,6 @@ mod provider_kind;
 mod resolver;
 
 pub use provider_kind::{ProviderKind, default_model, provider_from_name};
-pub use resolver::{ProviderSelection, resolve_key, resolve_provider_selection};
+pub use resolver::{
+    ProviderSelection, ensure_known_model, key_env_vars, resolve_key, resolve_provider_selection,
+};
@@ -6,6 +6,12 @@ pub enum ProviderKind {
 }
 
 impl ProviderKind {
+    pub const ALL: [ProviderKind; 3] = [
+        ProviderKind::OpenAI,
+        ProviderKind::Gemini,
+        ProviderKind::Claude,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             ProviderKind::OpenAI => "openai",
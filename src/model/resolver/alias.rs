@@ -0,0 +1,56 @@
+use anyhow::{Result, anyhow};
+use std::collections::BTreeMap;
+
+const MAX_ALIAS_DEPTH: usize = 8;
+
+/// Expands a user-defined alias (possibly chained) into a model argument.
+pub(super) fn expand_model_alias(raw: &str, aliases: &BTreeMap<String, String>) -> Result<String> {
+    let mut current = raw.trim().to_string();
+    let mut chain = vec![current.clone()];
+    for _ in 0..MAX_ALIAS_DEPTH {
+        let Some(target) = aliases.get(&current) else {
+            return Ok(current);
+        };
+        let target = target.trim().to_string();
+        if chain.contains(&target) {
+            chain.push(target);
+            return Err(anyhow!("model alias cycle: {}", chain.join(" -> ")));
+        }
+        chain.push(target.clone());
+        current = target;
+    }
+    Err(anyhow!(
+        "model alias '{}' nests deeper than {} levels",
+        raw.trim(),
+        MAX_ALIAS_DEPTH
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, target)| (name.to_string(), target.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn expands_alias_chain() {
+        let map = aliases(&[("fast", "quick"), ("quick", "gemini:gemini-2.5-flash")]);
+        assert_eq!(
+            expand_model_alias("fast", &map).unwrap(),
+            "gemini:gemini-2.5-flash"
+        );
+        assert_eq!(expand_model_alias("gpt-5.2", &map).unwrap(), "gpt-5.2");
+    }
+
+    #[test]
+    fn rejects_alias_cycle() {
+        let map = aliases(&[("a", "b"), ("b", "a")]);
+        let err = expand_model_alias("a", &map).unwrap_err();
+        assert!(err.to_string().contains("a -> b -> a"));
+    }
+}
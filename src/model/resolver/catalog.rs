@@ -0,0 +1,163 @@
+use anyhow::{Result, anyhow};
+
+use super::super::provider_kind::ProviderKind;
+
+const MAX_SUGGESTIONS: usize = 3;
+const NON_CHAT_MARKERS: &[&str] = &[
+    "embedding",
+    "embed",
+    "tts",
+    "whisper",
+    "transcribe",
+    "dall-e",
+    "imagen",
+    "image",
+    "moderation",
+    "audio",
+    "realtime",
+    "aqa",
+    "veo",
+];
+
+/// Checks a model name against the provider's model list (entries are `provider:model`),
+/// rejecting unknown or non-chat models with "did you mean" suggestions.
+pub(super) fn ensure_known_model_internal(
+    provider: ProviderKind,
+    requested: &str,
+    available: &[String],
+) -> Result<()> {
+    let prefix = format!("{}:", provider.as_str());
+    let names = available
+        .iter()
+        .filter_map(|entry| entry.strip_prefix(&prefix))
+        .map(|name| name.trim_start_matches("models/"))
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    let wanted = requested.trim().trim_start_matches("models/");
+    if names.contains(&wanted) {
+        if !is_chat_capable(wanted) {
+            return Err(anyhow!(
+                "model '{}' does not support chat/tool calling and cannot generate commands",
+                wanted
+            ));
+        }
+        return Ok(());
+    }
+
+    let suggestions = suggest_models(wanted, &names);
+    if suggestions.is_empty() {
+        return Err(anyhow!(
+            "unknown model '{}' for provider '{}' (see --show-models-list)",
+            wanted,
+            provider.as_str()
+        ));
+    }
+    Err(anyhow!(
+        "unknown model '{}' for provider '{}'; did you mean: {}?",
+        wanted,
+        provider.as_str(),
+        suggestions
+            .iter()
+            .map(|name| format!("{}:{}", provider.as_str(), name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+pub(super) fn is_chat_capable(model: &str) -> bool {
+    let lowered = model.to_lowercase();
+    !NON_CHAT_MARKERS
+        .iter()
+        .any(|marker| lowered.split(['-', '.', '_']).any(|part| part == *marker))
+}
+
+fn suggest_models<'a>(wanted: &str, names: &[&'a str]) -> Vec<&'a str> {
+    let wanted_lower = wanted.to_lowercase();
+    let threshold = (wanted.chars().count() / 3).max(2);
+    let mut scored = names
+        .iter()
+        .copied()
+        .filter(|name| is_chat_capable(name))
+        .filter_map(|name| {
+            let lowered = name.to_lowercase();
+            let distance = edit_distance(&wanted_lower, &lowered);
+            if distance <= threshold || lowered.starts_with(&wanted_lower) {
+                Some((distance, name))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    scored.sort();
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name)
+        .collect()
+}
+
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right_chars = right.chars().collect::<Vec<_>>();
+    let mut previous = (0..=right_chars.len()).collect::<Vec<_>>();
+    for (i, left_ch) in left.chars().enumerate() {
+        let mut current = vec![i + 1; right_chars.len() + 1];
+        for (j, right_ch) in right_chars.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_ch != *right_ch);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[right_chars.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> Vec<String> {
+        [
+            "openai:gpt-5.2",
+            "openai:gpt-5-mini",
+            "openai:text-embedding-3-small",
+            "gemini:gemini-2.5-flash",
+        ]
+        .iter()
+        .map(|entry| entry.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn accepts_known_model() {
+        ensure_known_model_internal(ProviderKind::OpenAI, "gpt-5.2", &catalog()).unwrap();
+    }
+
+    #[test]
+    fn suggests_close_model_names() {
+        let err =
+            ensure_known_model_internal(ProviderKind::OpenAI, "gpt-5.3", &catalog()).unwrap_err();
+        assert!(err.to_string().contains("did you mean: openai:gpt-5.2"));
+    }
+
+    #[test]
+    fn rejects_non_chat_model() {
+        let err =
+            ensure_known_model_internal(ProviderKind::OpenAI, "text-embedding-3-small", &catalog())
+                .unwrap_err();
+        assert!(err.to_string().contains("does not support chat"));
+    }
+
+    #[test]
+    fn skips_check_without_cached_models() {
+        ensure_known_model_internal(ProviderKind::Claude, "claude-anything", &catalog()).unwrap();
+    }
+
+    #[test]
+    fn computes_edit_distance() {
+        assert_eq!(edit_distance("flash", "flash"), 0);
+        assert_eq!(edit_distance("gpt-5.3", "gpt-5.2"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
@@ -2,6 +2,7 @@ use anyhow::{Result, anyhow};
 
 use super::super::provider_kind::ProviderKind;
 use super::env::get_env;
+use crate::credentials;
 
 pub(super) fn resolve_key_internal(
     provider: ProviderKind,
@@ -14,10 +15,18 @@ pub(super) fn resolve_key_internal(
         }
     }
 
+    if let Some(key) = key_env_vars(provider).iter().find_map(|name| get_env(name)) {
+        return Ok(key);
+    }
+
+    credentials::lookup_key(provider)?
+        .ok_or_else(|| anyhow!("API key not found for provider '{}'", provider.as_str()))
+}
+
+pub(super) fn key_env_vars(provider: ProviderKind) -> &'static [&'static str] {
     match provider {
-        ProviderKind::OpenAI => get_env("OPENAI_API_KEY"),
-        ProviderKind::Gemini => get_env("GEMINI_API_KEY").or_else(|| get_env("GOOGLE_API_KEY")),
-        ProviderKind::Claude => get_env("ANTHROPIC_API_KEY"),
+        ProviderKind::OpenAI => &["OPENAI_API_KEY"],
+        ProviderKind::Gemini => &["GEMINI_API_KEY", "GOOGLE_API_KEY"],
+        ProviderKind::Claude => &["ANTHROPIC_API_KEY"],
     }
-    .ok_or_else(|| anyhow!("API key not found for provider '{}'", provider.as_str()))
 }
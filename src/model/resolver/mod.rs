@@ -1,3 +1,5 @@
+mod alias;
+mod catalog;
 mod env;
 mod key;
 mod selection;
@@ -20,3 +22,11 @@ pub fn resolve_provider_selection(
 pub fn resolve_key(provider: ProviderKind, override_key: Option<&str>) -> Result<String> {
     key::resolve_key_internal(provider, override_key)
 }
+
+pub fn key_env_vars(provider: ProviderKind) -> &'static [&'static str] {
+    key::key_env_vars(provider)
+}
+
+pub fn ensure_known_model(provider: ProviderKind, model: &str, available: &[String]) -> Result<()> {
+    catalog::ensure_known_model_internal(provider, model, available)
+}
@@ -1,8 +1,10 @@
 use anyhow::{Result, anyhow};
 
 use super::super::provider_kind::{ProviderKind, provider_from_name};
+use super::alias::expand_model_alias;
 use super::env::{get_env, provider_from_model_name};
 use super::types::ProviderSelection;
+use crate::{config, credentials, meta};
 
 pub(super) fn resolve_provider_selection_internal(
     model_arg: Option<&str>,
@@ -10,7 +12,11 @@ pub(super) fn resolve_provider_selection_internal(
     allow_no_key: bool,
 ) -> Result<ProviderSelection> {
     match model_arg {
-        Some(model) => parse_model_arg(model, override_key, allow_no_key),
+        Some(model) => {
+            let aliases = config::load()?.model_aliases;
+            let expanded = expand_model_alias(model, &aliases)?;
+            parse_model_arg(&expanded, override_key, allow_no_key)
+        }
         None => default_provider_selection(override_key, allow_no_key),
     }
 }
@@ -86,12 +92,32 @@ fn infer_default_provider(override_key: Option<&str>, allow_no_key: bool) -> Res
         return Ok(ProviderKind::OpenAI);
     }
 
+    // Each stored-key lookup may run the credential helper, so the last used provider is
+    // tried first and a failing store only costs a warning.
+    let last_used = meta::get_last_using_provider().ok().flatten();
+    let candidates = last_used.into_iter().chain(
+        ProviderKind::ALL
+            .into_iter()
+            .filter(|provider| Some(*provider) != last_used),
+    );
+    for provider in candidates {
+        match credentials::lookup_key(provider) {
+            Ok(Some(_)) => return Ok(provider),
+            Ok(None) => {}
+            Err(err) => {
+                eprintln!("warning: failed to look up stored API keys: {err:#}");
+                break;
+            }
+        }
+    }
+
     if allow_no_key {
         return Ok(ProviderKind::OpenAI);
     }
 
     Err(anyhow!(
-        "no API key found (checked OPENAI_API_KEY, GEMINI_API_KEY/GOOGLE_API_KEY, ANTHROPIC_API_KEY, and --key)"
+        "no API key found (checked OPENAI_API_KEY, GEMINI_API_KEY/GOOGLE_API_KEY, ANTHROPIC_API_KEY, --key, and {}); run `command-generator auth login <provider>`",
+        credentials::active_store()?.describe()
     ))
 }
 
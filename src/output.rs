@@ -1,9 +1,20 @@
 use anyhow::Result;
 
+use crate::clipboard;
+use crate::prompter::{ClarificationKind, ClarificationPrompter};
 use crate::request_engine::HandleResult;
+use crate::validation::{FsPreview, RiskAssessment};
 
 pub fn print_generated_result(result: &HandleResult, explanation_mode: bool) -> Result<()> {
     println!("{}", result.command);
+    // On stderr so that `$(cg --once ...)` still captures just the command.
+    eprintln!("risk: {}", result.risk);
+    for warning in &result.warnings {
+        eprintln!("warning: {warning}");
+    }
+    if let Some(preview) = &result.fs_preview {
+        print_fs_preview(preview);
+    }
     println!();
     if explanation_mode {
         if result.explanations.is_empty() {
@@ -16,3 +27,69 @@ pub fn print_generated_result(result: &HandleResult, explanation_mode: bool) ->
     }
     Ok(())
 }
+
+/// Paths listed per kind before the rest are summarized as a count.
+const PREVIEW_PATH_LIMIT: usize = 10;
+
+fn print_fs_preview(preview: &FsPreview) {
+    if preview.is_empty() {
+        eprintln!("dry run: no files in the working directory would change");
+    } else {
+        eprintln!(
+            "dry run: {} created, {} modified, {} deleted in the working directory",
+            preview.created.len(),
+            preview.modified.len(),
+            preview.deleted.len()
+        );
+        for (marker, paths) in [
+            ('+', &preview.created),
+            ('~', &preview.modified),
+            ('-', &preview.deleted),
+        ] {
+            for path in paths.iter().take(PREVIEW_PATH_LIMIT) {
+                eprintln!("  {marker} {path}");
+            }
+            if paths.len() > PREVIEW_PATH_LIMIT {
+                eprintln!(
+                    "  {marker} ... and {} more",
+                    paths.len() - PREVIEW_PATH_LIMIT
+                );
+            }
+        }
+    }
+    if let Some(note) = &preview.note {
+        eprintln!("  note: {note}");
+    }
+}
+
+/// Copies `command` to the clipboard. High-risk commands are only copied once the user
+/// confirms, so without a prompter (`--once`) they are left for the user to copy by hand.
+/// Returns whether the command was copied.
+pub fn copy_command(
+    command: &str,
+    risk: &RiskAssessment,
+    prompter: Option<&mut dyn ClarificationPrompter>,
+) -> Result<bool> {
+    if risk.is_high() {
+        let Some(prompter) = prompter else {
+            eprintln!(
+                "warning: not copied: {} command needs confirmation; copy it manually or use /copy in interactive mode",
+                risk.level.label()
+            );
+            return Ok(false);
+        };
+        let question = format!(
+            "This command is {}. Copy it to the clipboard?",
+            risk.level.label()
+        );
+        if prompter.ask(ClarificationKind::YesNo, &question)? != "yes" {
+            println!("Not copied.");
+            return Ok(false);
+        }
+    }
+    if let Err(err) = clipboard::copy_text(command) {
+        eprintln!("warning: failed to copy command: {err}");
+        return Ok(false);
+    }
+    Ok(true)
+}
@@ -19,6 +19,18 @@ pub fn sessions_dir() -> PathBuf {
     base_dir().join("sessions")
 }
 
+pub fn sessions_db_path() -> PathBuf {
+    base_dir().join("sessions.db")
+}
+
+pub fn config_path() -> PathBuf {
+    base_dir().join("config.json")
+}
+
+pub fn prompts_dir() -> PathBuf {
+    base_dir().join("prompts")
+}
+
 pub fn ensure_dirs() -> anyhow::Result<()> {
     std::fs::create_dir_all(cache_dir())?;
     std::fs::create_dir_all(sessions_dir())?;
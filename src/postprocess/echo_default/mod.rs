@@ -1,6 +1,4 @@
 mod normalize;
-mod split;
-mod words;
 
 use anyhow::Result;
 
@@ -30,5 +28,9 @@ mod tests {
             normalize_echo_default("builtin echo hello"),
             "builtin echo -e hello"
         );
+        assert_eq!(
+            normalize_echo_default("if true; then echo \"a; echo b\" > out; fi"),
+            "if true; then echo -e \"a; echo b\" > out; fi"
+        );
     }
 }
@@ -1,77 +1,38 @@
-use super::split::split_top_level_segment_ranges;
-use super::words::{is_echo_word, next_word_bounds};
+use crate::shell;
 
 pub(super) fn normalize_echo_default(command: &str) -> String {
-    let ranges = split_top_level_segment_ranges(command);
-    if ranges.is_empty() {
-        return command.trim().to_string();
-    }
-
-    let mut out = String::new();
-    let mut cursor = 0usize;
-    for (start, end) in ranges {
-        if cursor < start {
-            out.push_str(&command[cursor..start]);
-        }
-        out.push_str(&normalize_echo_segment(&command[start..end]));
-        cursor = end;
-    }
-    if cursor < command.len() {
-        out.push_str(&command[cursor..]);
-    }
-    out.trim().to_string()
-}
-
-fn normalize_echo_segment(segment: &str) -> String {
-    let trimmed = segment.trim();
-    if trimmed.is_empty() {
-        return segment.to_string();
-    }
-
-    let leading = segment.len() - segment.trim_start().len();
-    let trailing = segment.len() - segment.trim_end().len();
-    let core_end = segment.len().saturating_sub(trailing);
-    let core = &segment[leading..core_end];
-
-    let Some(first) = next_word_bounds(core, 0) else {
-        return segment.to_string();
+    let trimmed = command.trim();
+    let Ok(script) = shell::parse(trimmed) else {
+        return trimmed.to_string();
     };
-    let first_word = &core[first.0..first.1];
 
-    let (_, after_echo) = if first_word == "builtin" || first_word == "command" {
-        let Some(second) = next_word_bounds(core, first.1) else {
-            return segment.to_string();
+    let mut insertions = Vec::new();
+    for simple in shell::simple_commands(&script) {
+        let words = &simple.words;
+        let raw = |index: usize| {
+            words
+                .get(index)
+                .map(|word| &trimmed[word.span.start..word.span.end])
+        };
+        let echo_index = match raw(0) {
+            Some("builtin" | "command") => 1,
+            _ => 0,
+        };
+        let Some(echo) = raw(echo_index) else {
+            continue;
         };
-        let second_word = &core[second.0..second.1];
-        if !is_echo_word(second_word) {
-            return segment.to_string();
+        if !is_echo_word(echo) {
+            continue;
         }
-        (second, second.1)
-    } else {
-        if !is_echo_word(first_word) {
-            return segment.to_string();
+        match raw(echo_index + 1) {
+            Some(arg) if arg.starts_with('-') => {}
+            Some(_) => insertions.push((words[echo_index + 1].span.start, "-e ".to_string())),
+            None => insertions.push((words[echo_index].span.end, " -e".to_string())),
         }
-        (first, first.1)
-    };
-
-    let Some(arg) = next_word_bounds(core, after_echo) else {
-        let mut with_flag = String::new();
-        with_flag.push_str(&segment[..leading]);
-        with_flag.push_str(core);
-        with_flag.push_str(" -e");
-        with_flag.push_str(&segment[core_end..]);
-        return with_flag;
-    };
-    let arg_word = &core[arg.0..arg.1];
-    if arg_word.starts_with('-') {
-        return segment.to_string();
     }
+    shell::apply_insertions(trimmed, insertions)
+}
 
-    let mut with_flag = String::new();
-    with_flag.push_str(&segment[..leading]);
-    with_flag.push_str(&core[..arg.0]);
-    with_flag.push_str("-e ");
-    with_flag.push_str(&core[arg.0..]);
-    with_flag.push_str(&segment[core_end..]);
-    with_flag
+fn is_echo_word(word: &str) -> bool {
+    word == "echo" || word == "\\echo"
 }
@@ -1,7 +1,11 @@
+mod templates;
+
 use anyhow::{Context, Result};
 use serde::Serialize;
 use tera::{Context as TeraContext, Tera};
 
+pub use templates::{PromptTemplates, TemplateSource};
+
 #[derive(Debug, Clone, Serialize)]
 pub struct PromptTurn {
     pub user_input: String,
@@ -18,6 +22,8 @@ pub struct PromptClarification {
 pub struct PromptInput {
     pub os: String,
     pub shell: String,
+    /// Installed tool flavors and versions, e.g. `sed GNU 4.9` or `jq not installed`.
+    pub tools: Vec<String>,
     pub session_uuid: String,
     pub model: String,
     pub command_tool_name: String,
@@ -37,21 +43,27 @@ pub struct RenderedPrompt {
     pub user: String,
 }
 
-const SYSTEM_PROMPT_TEMPLATE: &str = include_str!("prompts/system_prompt.tera");
-const USER_PROMPT_TEMPLATE: &str = include_str!("prompts/user_prompt.tera");
-
 pub fn render(input: &PromptInput) -> Result<RenderedPrompt> {
+    render_with(&PromptTemplates::builtin(), input)
+}
+
+pub fn render_with(templates: &PromptTemplates, input: &PromptInput) -> Result<RenderedPrompt> {
     let mut context = TeraContext::new();
     context.insert("command_tool_name", &input.command_tool_name);
     context.insert("question_tool_name", &input.question_tool_name);
     context.insert("text_question_tool_name", &input.text_question_tool_name);
     context.insert("explanation_mode", &input.explanation_mode);
 
-    let system = Tera::one_off(SYSTEM_PROMPT_TEMPLATE, &context, false)
-        .with_context(|| "failed to render system prompt")?;
+    let system = Tera::one_off(&templates.system, &context, false).with_context(|| {
+        format!(
+            "failed to render system prompt ({})",
+            templates.system_source
+        )
+    })?;
 
     context.insert("os", &input.os);
     context.insert("shell", &input.shell);
+    context.insert("tools", &input.tools);
     context.insert("session_uuid", &input.session_uuid);
     context.insert("model", &input.model);
     context.insert("text_question_tool_name", &input.text_question_tool_name);
@@ -63,8 +75,8 @@ pub fn render(input: &PromptInput) -> Result<RenderedPrompt> {
     context.insert("feedback", &input.feedback);
     context.insert("explanation_mode", &input.explanation_mode);
 
-    let user = Tera::one_off(USER_PROMPT_TEMPLATE, &context, false)
-        .with_context(|| "failed to render user prompt")?;
+    let user = Tera::one_off(&templates.user, &context, false)
+        .with_context(|| format!("failed to render user prompt ({})", templates.user_source))?;
 
     Ok(RenderedPrompt { system, user })
 }
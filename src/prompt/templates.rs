@@ -0,0 +1,179 @@
+use anyhow::{Context, Result, anyhow};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use super::{PromptClarification, PromptInput, PromptTurn, render_with};
+use crate::paths;
+
+const SYSTEM_PROMPT_FILE: &str = "system_prompt.tera";
+const USER_PROMPT_FILE: &str = "user_prompt.tera";
+const SYSTEM_PROMPT_TEMPLATE: &str = include_str!("../prompts/system_prompt.tera");
+const USER_PROMPT_TEMPLATE: &str = include_str!("../prompts/user_prompt.tera");
+
+const SENTINEL_PREFIX: &str = "__command_generator_";
+const REQUIRED_SYSTEM_VARIABLES: &[&str] = &["command_tool_name"];
+const REQUIRED_USER_VARIABLES: &[&str] = &["user_input", "feedback"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateSource {
+    Builtin,
+    File(PathBuf),
+}
+
+impl fmt::Display for TemplateSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateSource::Builtin => write!(f, "built-in"),
+            TemplateSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PromptTemplates {
+    pub system: String,
+    pub user: String,
+    pub system_source: TemplateSource,
+    pub user_source: TemplateSource,
+}
+
+impl PromptTemplates {
+    pub fn builtin() -> Self {
+        Self {
+            system: SYSTEM_PROMPT_TEMPLATE.to_string(),
+            user: USER_PROMPT_TEMPLATE.to_string(),
+            system_source: TemplateSource::Builtin,
+            user_source: TemplateSource::Builtin,
+        }
+    }
+
+    /// Loads templates from `<base>/prompts/<profile>/`, then `<base>/prompts/`,
+    /// falling back to the built-in template for each file that is not found.
+    pub fn load(profile: Option<&str>) -> Result<Self> {
+        let dirs = template_dirs(profile)?;
+        let (system, system_source) = load_template(&dirs, SYSTEM_PROMPT_FILE)?
+            .unwrap_or_else(|| (SYSTEM_PROMPT_TEMPLATE.to_string(), TemplateSource::Builtin));
+        let (user, user_source) = load_template(&dirs, USER_PROMPT_FILE)?
+            .unwrap_or_else(|| (USER_PROMPT_TEMPLATE.to_string(), TemplateSource::Builtin));
+        let templates = Self {
+            system,
+            user,
+            system_source,
+            user_source,
+        };
+        templates.validate()?;
+        Ok(templates)
+    }
+
+    /// Renders both templates against sample input and ensures the variables the
+    /// request engine depends on actually reach the output.
+    pub fn validate(&self) -> Result<()> {
+        let rendered = render_with(self, &sample_input())?;
+        for name in REQUIRED_SYSTEM_VARIABLES {
+            if !rendered.system.contains(&sentinel(name)) {
+                return Err(anyhow!(
+                    "system prompt template ({}) must render '{{{{ {} }}}}'",
+                    self.system_source,
+                    name
+                ));
+            }
+        }
+        for name in REQUIRED_USER_VARIABLES {
+            if !rendered.user.contains(&sentinel(name)) {
+                return Err(anyhow!(
+                    "user prompt template ({}) must render '{{{{ {} }}}}'",
+                    self.user_source,
+                    name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn template_dirs(profile: Option<&str>) -> Result<Vec<PathBuf>> {
+    let base = paths::prompts_dir();
+    let mut dirs = Vec::new();
+    if let Some(profile) = profile.map(str::trim).filter(|value| !value.is_empty()) {
+        if profile.contains(['/', '\\']) || profile == "." || profile == ".." {
+            return Err(anyhow!("invalid profile name '{}'", profile));
+        }
+        dirs.push(base.join(profile));
+    }
+    dirs.push(base);
+    Ok(dirs)
+}
+
+fn load_template(dirs: &[PathBuf], file_name: &str) -> Result<Option<(String, TemplateSource)>> {
+    for dir in dirs {
+        let path = dir.join(file_name);
+        if !path.is_file() {
+            continue;
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read prompt template: {}", path.display()))?;
+        return Ok(Some((content, TemplateSource::File(path))));
+    }
+    Ok(None)
+}
+
+fn sentinel(name: &str) -> String {
+    format!("{}{}__", SENTINEL_PREFIX, name)
+}
+
+fn sample_input() -> PromptInput {
+    PromptInput {
+        os: sentinel("os"),
+        shell: sentinel("shell"),
+        tools: vec![sentinel("tools")],
+        session_uuid: sentinel("session_uuid"),
+        model: sentinel("model"),
+        command_tool_name: sentinel("command_tool_name"),
+        question_tool_name: sentinel("question_tool_name"),
+        text_question_tool_name: sentinel("text_question_tool_name"),
+        user_input: sentinel("user_input"),
+        shell_history: vec![sentinel("shell_history")],
+        generated_history: vec![sentinel("generated_history")],
+        turns: vec![PromptTurn {
+            user_input: sentinel("turn_user_input"),
+            command: sentinel("turn_command"),
+        }],
+        clarifications: vec![PromptClarification {
+            question: sentinel("clarification_question"),
+            answer: sentinel("clarification_answer"),
+        }],
+        feedback: Some(sentinel("feedback")),
+        explanation_mode: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_templates_pass_validation() {
+        PromptTemplates::builtin().validate().unwrap();
+    }
+
+    #[test]
+    fn rejects_user_template_without_request() {
+        let mut templates = PromptTemplates::builtin();
+        templates.user = "Environment: {{ os }}\n{{ feedback }}".to_string();
+        let err = templates.validate().unwrap_err();
+        assert!(err.to_string().contains("user_input"));
+    }
+
+    #[test]
+    fn reports_template_syntax_errors() {
+        let mut templates = PromptTemplates::builtin();
+        templates.system = "{% if %}".to_string();
+        assert!(templates.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_profile_with_path_separator() {
+        assert!(template_dirs(Some("../etc")).is_err());
+    }
+}
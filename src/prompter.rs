@@ -1,5 +1,7 @@
 use anyhow::{Result, anyhow};
 use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::{Editor, Helper};
 use std::io::{self, BufRead, Write};
 
 #[derive(Debug, Clone, Copy)]
@@ -12,17 +14,17 @@ pub trait ClarificationPrompter {
     fn ask(&mut self, kind: ClarificationKind, question: &str) -> Result<String>;
 }
 
-pub struct EditorPrompter<'a> {
-    editor: &'a mut rustyline::DefaultEditor,
+pub struct EditorPrompter<'a, H: Helper> {
+    editor: &'a mut Editor<H, DefaultHistory>,
 }
 
-impl<'a> EditorPrompter<'a> {
-    pub fn new(editor: &'a mut rustyline::DefaultEditor) -> Self {
+impl<'a, H: Helper> EditorPrompter<'a, H> {
+    pub fn new(editor: &'a mut Editor<H, DefaultHistory>) -> Self {
         Self { editor }
     }
 }
 
-impl ClarificationPrompter for EditorPrompter<'_> {
+impl<H: Helper> ClarificationPrompter for EditorPrompter<'_, H> {
     fn ask(&mut self, kind: ClarificationKind, question: &str) -> Result<String> {
         match kind {
             ClarificationKind::YesNo => ask_yes_no_with_editor(self.editor, question),
@@ -54,7 +56,10 @@ impl ClarificationPrompter for StdioPrompter {
     }
 }
 
-fn ask_yes_no_with_editor(editor: &mut rustyline::DefaultEditor, question: &str) -> Result<String> {
+fn ask_yes_no_with_editor<H: Helper>(
+    editor: &mut Editor<H, DefaultHistory>,
+    question: &str,
+) -> Result<String> {
     loop {
         let prompt = format!("? {} [y/n]: ", question.trim());
         match editor.readline(&prompt) {
@@ -73,7 +78,10 @@ fn ask_yes_no_with_editor(editor: &mut rustyline::DefaultEditor, question: &str)
     }
 }
 
-fn ask_text_with_editor(editor: &mut rustyline::DefaultEditor, question: &str) -> Result<String> {
+fn ask_text_with_editor<H: Helper>(
+    editor: &mut Editor<H, DefaultHistory>,
+    question: &str,
+) -> Result<String> {
     loop {
         let prompt = format!("? {}: ", question.trim());
         match editor.readline(&prompt) {
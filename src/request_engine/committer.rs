@@ -1,9 +1,8 @@
 use anyhow::Result;
 
-use crate::cli::Cli;
 use crate::llm::CommandExplanationItem;
 use crate::request_engine::types::HandleResult;
-use crate::session::{self, SessionRecord};
+use crate::session::{self, SessionRecord, TurnEnvironment};
 use crate::validation::ValidationReport;
 
 pub trait CommandCommitter: Send + Sync {
@@ -18,17 +17,17 @@ pub trait CommandCommitter: Send + Sync {
     ) -> Result<HandleResult>;
 }
 
-pub struct SessionCommandCommitter<'a> {
-    cli: &'a Cli,
+pub struct SessionCommandCommitter {
+    explanation: bool,
 }
 
-impl<'a> SessionCommandCommitter<'a> {
-    pub fn new(cli: &'a Cli) -> Self {
-        Self { cli }
+impl SessionCommandCommitter {
+    pub fn new(explanation: bool) -> Self {
+        Self { explanation }
     }
 }
 
-impl CommandCommitter for SessionCommandCommitter<'_> {
+impl CommandCommitter for SessionCommandCommitter {
     fn commit(
         &self,
         user_input: &str,
@@ -38,20 +37,22 @@ impl CommandCommitter for SessionCommandCommitter<'_> {
         explanations: Vec<CommandExplanationItem>,
         report: ValidationReport,
     ) -> Result<HandleResult> {
-        if self.cli.copy
-            && let Err(err) = crate::clipboard::copy_text(&command)
-        {
-            eprintln!("warning: failed to copy command: {err}");
-        }
+        let risk = report
+            .risk
+            .clone()
+            .unwrap_or_else(|| crate::validation::assess_risk(&command));
+        let warnings = report.warnings();
+        let fs_preview = report.fs_preview.clone();
         session.push_turn(
             user_input,
             command.clone(),
             reason,
             explanations.clone(),
             report,
+            Some(TurnEnvironment::capture()),
         );
         session::save_session(session)?;
-        let explanations = if self.cli.explanation {
+        let explanations = if self.explanation {
             explanations
         } else {
             Vec::new()
@@ -59,6 +60,9 @@ impl CommandCommitter for SessionCommandCommitter<'_> {
         Ok(HandleResult {
             command,
             explanations,
+            risk,
+            warnings,
+            fs_preview,
         })
     }
 }
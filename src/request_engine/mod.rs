@@ -20,15 +20,16 @@ pub use types::HandleResult;
 
 pub struct RequestEngine<'a> {
     cli: &'a Cli,
-    gateway: &'a dyn gateway::GenerationGateway,
+    gateway: Box<dyn gateway::GenerationGateway>,
     post_processor: Box<dyn CommandPostProcessor>,
     validator: Box<dyn CommandValidator>,
+    explanation: bool,
 }
 
 impl<'a> RequestEngine<'a> {
     pub fn new(
         cli: &'a Cli,
-        gateway: &'a dyn gateway::GenerationGateway,
+        gateway: Box<dyn gateway::GenerationGateway>,
         post_processor: Box<dyn CommandPostProcessor>,
         validator: Box<dyn CommandValidator>,
     ) -> Self {
@@ -37,9 +38,27 @@ impl<'a> RequestEngine<'a> {
             gateway,
             post_processor,
             validator,
+            explanation: cli.explanation,
         }
     }
 
+    pub fn model_name(&self) -> &str {
+        self.gateway.model_name()
+    }
+
+    /// Swaps the model backend, e.g. after `/model` in interactive mode.
+    pub fn replace_gateway(&mut self, gateway: Box<dyn gateway::GenerationGateway>) {
+        self.gateway = gateway;
+    }
+
+    pub fn explanation_mode(&self) -> bool {
+        self.explanation
+    }
+
+    pub fn set_explanation_mode(&mut self, enabled: bool) {
+        self.explanation = enabled;
+    }
+
     pub async fn generate(
         &self,
         user_input: &str,
@@ -49,9 +68,10 @@ impl<'a> RequestEngine<'a> {
         orchestrator::run(
             orchestrator::EngineDeps {
                 cli: self.cli,
-                gateway: self.gateway,
+                gateway: self.gateway.as_ref(),
                 post_processor: self.post_processor.as_ref(),
                 validator: self.validator.as_ref(),
+                explanation: self.explanation,
             },
             user_input,
             session,
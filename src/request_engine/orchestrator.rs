@@ -20,6 +20,7 @@ pub struct EngineDeps<'a> {
     pub gateway: &'a dyn GenerationGateway,
     pub post_processor: &'a dyn CommandPostProcessor,
     pub validator: &'a dyn CommandValidator,
+    pub explanation: bool,
 }
 
 pub async fn run(
@@ -31,14 +32,14 @@ pub async fn run(
     let context =
         PromptStaticContext::new(deps.cli, deps.gateway.model_name(), user_input, session)?;
     let mut state = RuntimeState::new(deps.cli.max_attempts.max(1), 8);
-    let committer = SessionCommandCommitter::new(deps.cli);
+    let committer = SessionCommandCommitter::new(deps.explanation);
 
     while state.can_attempt_command() {
         let rendered = context.render(
             &session.uuid,
             state.clarifications().to_vec(),
             state.feedback().cloned(),
-            deps.cli.explanation,
+            deps.explanation,
         )?;
 
         match deps
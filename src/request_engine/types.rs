@@ -1,6 +1,12 @@
 use crate::llm::CommandExplanationItem;
+use crate::validation::{FsPreview, RiskAssessment};
 
 pub struct HandleResult {
     pub command: String,
     pub explanations: Vec<CommandExplanationItem>,
+    pub risk: RiskAssessment,
+    /// Validation findings that did not block the command, such as shellcheck warnings.
+    pub warnings: Vec<String>,
+    /// Files the command changed during a sandboxed dry run.
+    pub fs_preview: Option<FsPreview>,
 }
@@ -0,0 +1,114 @@
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value};
+
+/// Upgrades a document from version `n` to `n + 1`.
+pub type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// A versioned JSON document layout: the field holding the version and the migrations
+/// that lead to the current one. `migrations[i]` upgrades version `i + 1` to `i + 2`, so
+/// the current version is `migrations.len() + 1`.
+pub struct Schema {
+    pub name: &'static str,
+    pub version_field: &'static str,
+    pub migrations: &'static [Migration],
+}
+
+impl Schema {
+    pub const fn current_version(&self) -> u32 {
+        self.migrations.len() as u32 + 1
+    }
+
+    /// Version stored in `value`. Documents written before versioning are version 1.
+    pub fn version_of(&self, value: &Value) -> Result<u32> {
+        match value.get(self.version_field) {
+            None | Some(Value::Null) => Ok(1),
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .filter(|version| *version >= 1)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "invalid {} '{}': {}",
+                        self.name,
+                        self.version_field,
+                        version
+                    )
+                }),
+        }
+    }
+
+    /// Runs every migration newer than the stored version and stamps the current version.
+    /// Returns the version the document had before, and fails for documents written by a
+    /// newer build.
+    pub fn upgrade(&self, value: &mut Value) -> Result<u32> {
+        let from = self.version_of(value)?;
+        let current = self.current_version();
+        if from > current {
+            return Err(anyhow!(
+                "{} schema version {} is newer than supported version {}; upgrade command-generator",
+                self.name,
+                from,
+                current
+            ));
+        }
+        let object = value
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("{} is not a JSON object", self.name))?;
+        for (index, migration) in self.migrations.iter().enumerate().skip(from as usize - 1) {
+            migration(object).map_err(|err| {
+                anyhow!(
+                    "failed to upgrade {} from version {}: {err:#}",
+                    self.name,
+                    index + 1
+                )
+            })?;
+        }
+        object.insert(self.version_field.to_string(), Value::from(current));
+        Ok(from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn add_b(object: &mut Map<String, Value>) -> Result<()> {
+        object.insert("b".to_string(), json!(true));
+        Ok(())
+    }
+
+    fn rename_a(object: &mut Map<String, Value>) -> Result<()> {
+        if let Some(a) = object.remove("a") {
+            object.insert("c".to_string(), a);
+        }
+        Ok(())
+    }
+
+    const TEST_SCHEMA: Schema = Schema {
+        name: "test document",
+        version_field: "version",
+        migrations: &[add_b, rename_a],
+    };
+
+    #[test]
+    fn runs_pending_migrations_in_order() {
+        let mut legacy = json!({ "a": 1 });
+        assert_eq!(TEST_SCHEMA.upgrade(&mut legacy).unwrap(), 1);
+        assert_eq!(legacy, json!({ "b": true, "c": 1, "version": 3 }));
+
+        let mut partial = json!({ "a": 1, "version": 2 });
+        TEST_SCHEMA.upgrade(&mut partial).unwrap();
+        assert_eq!(partial, json!({ "c": 1, "version": 3 }));
+    }
+
+    #[test]
+    fn rejects_newer_and_invalid_versions() {
+        let err = TEST_SCHEMA
+            .upgrade(&mut json!({ "version": 9 }))
+            .unwrap_err();
+        assert!(err.to_string().contains("newer"));
+        assert!(TEST_SCHEMA.upgrade(&mut json!({ "version": 0 })).is_err());
+        assert!(TEST_SCHEMA.upgrade(&mut json!([1])).is_err());
+    }
+}
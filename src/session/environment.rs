@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where a command was generated, recorded on each turn.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnEnvironment {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    #[serde(default)]
+    pub os: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_root: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+}
+
+impl TurnEnvironment {
+    pub fn capture() -> Self {
+        let cwd = std::env::current_dir().ok();
+        let git_dir = cwd.as_deref().and_then(find_git_dir);
+        Self {
+            cwd: cwd.as_ref().map(|dir| dir.display().to_string()),
+            shell: std::env::var("SHELL")
+                .ok()
+                .filter(|value| !value.trim().is_empty()),
+            os: std::env::consts::OS.to_string(),
+            git_root: git_dir.as_ref().map(|(root, _)| root.display().to_string()),
+            git_branch: git_dir.and_then(|(_, git_dir)| read_branch(&git_dir)),
+        }
+    }
+
+    /// Key used to group turns by project: the git repository root, or the working
+    /// directory outside a repository.
+    pub fn project(&self) -> Option<&str> {
+        self.git_root.as_deref().or(self.cwd.as_deref())
+    }
+}
+
+/// Walks up from `start` to the closest `.git` entry and returns the worktree root and
+/// the resolved git directory (following `gitdir:` files used by worktrees and submodules).
+fn find_git_dir(start: &Path) -> Option<(PathBuf, PathBuf)> {
+    for dir in start.ancestors() {
+        let candidate = dir.join(".git");
+        if candidate.is_dir() {
+            return Some((dir.to_path_buf(), candidate));
+        }
+        if candidate.is_file() {
+            let content = fs::read_to_string(&candidate).ok()?;
+            let target = content.trim().strip_prefix("gitdir:")?.trim();
+            return Some((dir.to_path_buf(), dir.join(target)));
+        }
+    }
+    None
+}
+
+fn read_branch(git_dir: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    parse_head(&head)
+}
+
+/// Returns the branch name for a symbolic `HEAD`, or a short hash when detached.
+fn parse_head(head: &str) -> Option<String> {
+    let head = head.trim();
+    if let Some(reference) = head.strip_prefix("ref:") {
+        let reference = reference.trim();
+        return Some(
+            reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_string(),
+        );
+    }
+    if head.len() >= 7 && head.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return Some(head[..7].to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_symbolic_and_detached_head() {
+        assert_eq!(
+            parse_head("ref: refs/heads/feature/x\n").as_deref(),
+            Some("feature/x")
+        );
+        assert_eq!(
+            parse_head("0123456789abcdef0123456789abcdef01234567").as_deref(),
+            Some("0123456")
+        );
+        assert_eq!(parse_head("garbage"), None);
+    }
+
+    #[test]
+    fn project_prefers_git_root() {
+        let environment = TurnEnvironment {
+            cwd: Some("/repo/src".to_string()),
+            git_root: Some("/repo".to_string()),
+            ..TurnEnvironment::default()
+        };
+        assert_eq!(environment.project(), Some("/repo"));
+    }
+}
@@ -0,0 +1,269 @@
+use anyhow::{Result, anyhow};
+use std::path::Path;
+
+use super::environment::TurnEnvironment;
+use super::record::{SessionRecord, SessionTurn};
+use super::time::format_timestamp;
+use crate::validation::ValidationReport;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Shell,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "sh" | "shell" => Ok(Self::Shell),
+            "json" => Ok(Self::Json),
+            other => Err(anyhow!(
+                "unknown export format '{}' (use md, sh or json)",
+                other
+            )),
+        }
+    }
+}
+
+pub fn export_session(session: &SessionRecord, format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Markdown => Ok(render_markdown(session)),
+        ExportFormat::Shell => Ok(render_shell(session)),
+        ExportFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(session)?)),
+    }
+}
+
+fn render_markdown(session: &SessionRecord) -> String {
+    let mut out = String::new();
+    let title = session
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("Session {}", session.uuid));
+    out.push_str(&format!("# {}\n\n", title));
+    out.push_str(&format!("- Session: `{}`\n", session.uuid));
+    out.push_str(&format!(
+        "- Model: `{}:{}`\n",
+        session.provider, session.model
+    ));
+    out.push_str(&format!(
+        "- Created: {} UTC\n",
+        format_timestamp(session.created_at)
+    ));
+    if let Some(parent) = &session.parent {
+        out.push_str(&format!(
+            "- Forked from: `{}` after turn {}\n",
+            parent.uuid, parent.turn_index
+        ));
+    }
+
+    for (index, turn) in session.turns.iter().enumerate() {
+        out.push_str(&format!(
+            "\n## {}. {}\n\n",
+            index + 1,
+            first_line(&turn.user_input)
+        ));
+        if turn.user_input.trim().lines().count() > 1 {
+            for line in turn.user_input.trim().lines() {
+                out.push_str(&format!("> {}\n", line));
+            }
+            out.push('\n');
+        }
+        if let Some(location) = turn.environment.as_ref().and_then(describe_location) {
+            out.push_str(&format!("Run in {}\n\n", location));
+        }
+        let fence = code_fence(&turn.command);
+        out.push_str(&format!(
+            "{}{}\n{}\n{}\n",
+            fence,
+            fence_language(turn),
+            turn.command,
+            fence
+        ));
+        if !turn.reason.trim().is_empty() {
+            out.push_str(&format!("\n{}\n", turn.reason.trim()));
+        }
+        if !turn.explanations.is_empty() {
+            out.push('\n');
+            for item in &turn.explanations {
+                out.push_str(&format!(
+                    "- `{}` ({}): {}\n",
+                    item.value, item.kind, item.explanation
+                ));
+            }
+        }
+        out.push_str(&format!(
+            "\n_Validation: {}_\n",
+            validation_summary(&turn.validation)
+        ));
+    }
+    out
+}
+
+fn render_shell(session: &SessionRecord) -> String {
+    let shell = session
+        .turns
+        .iter()
+        .map(|turn| shell_name(&turn.validation.shell))
+        .find(|shell| !shell.is_empty())
+        .unwrap_or("sh");
+    let mut out = format!("#!/usr/bin/env {}\n", shell);
+    out.push_str(&format!(
+        "# Exported from command-generator session {} ({}:{})\n",
+        session.uuid, session.provider, session.model
+    ));
+    if let Some(name) = &session.name {
+        out.push_str(&format!("# Name: {}\n", name));
+    }
+    for (index, turn) in session.turns.iter().enumerate() {
+        out.push('\n');
+        for (line_index, line) in turn.user_input.trim().lines().enumerate() {
+            if line_index == 0 {
+                out.push_str(&format!("# {}. {}\n", index + 1, line));
+            } else {
+                out.push_str(&format!("#    {}\n", line));
+            }
+        }
+        for line in turn.reason.trim().lines() {
+            out.push_str(&format!("#    {}\n", line));
+        }
+        if let Some(location) = turn.environment.as_ref().and_then(describe_location) {
+            out.push_str(&format!("#    in {}\n", location.replace('`', "")));
+        }
+        out.push_str(&turn.command);
+        out.push('\n');
+    }
+    out
+}
+
+fn validation_summary(report: &ValidationReport) -> String {
+    let mut parts = Vec::new();
+    let shell = if report.shell.is_empty() {
+        "shell"
+    } else {
+        report.shell.as_str()
+    };
+    if report.syntax_ok {
+        parts.push(format!("syntax ok ({})", shell));
+    } else {
+        parts.push(format!("syntax check failed ({})", shell));
+    }
+    if !report.checked_binaries.is_empty() {
+        parts.push(format!(
+            "commands found: {}",
+            report.checked_binaries.join(", ")
+        ));
+    }
+    if report.runtime_checked {
+        parts.push(if report.runtime_ok {
+            "runtime check passed".to_string()
+        } else {
+            "runtime check failed".to_string()
+        });
+    }
+    if !report.is_valid() {
+        parts.push(report.to_feedback_text());
+    }
+    parts.join("; ")
+}
+
+fn describe_location(environment: &TurnEnvironment) -> Option<String> {
+    let cwd = environment.cwd.as_deref()?;
+    let mut location = format!("`{}`", cwd);
+    if let Some(branch) = &environment.git_branch {
+        location.push_str(&format!(" (git branch `{}`)", branch));
+    }
+    Some(location)
+}
+
+fn first_line(text: &str) -> &str {
+    text.trim().lines().next().unwrap_or_default()
+}
+
+fn fence_language(turn: &SessionTurn) -> &str {
+    match shell_name(&turn.validation.shell) {
+        shell @ ("bash" | "zsh" | "fish") => shell,
+        _ => "sh",
+    }
+}
+
+/// The program name of a recorded shell, which is usually a path such as `/bin/bash`.
+fn shell_name(shell: &str) -> &str {
+    Path::new(shell)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(shell)
+}
+
+/// A backtick fence longer than any backtick run inside `content`.
+fn code_fence(content: &str) -> String {
+    let longest = content
+        .split(|ch| ch != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ProviderKind;
+
+    fn sample() -> SessionRecord {
+        let mut session = SessionRecord::new(ProviderKind::OpenAI, "gpt-5.2");
+        session.push_turn(
+            "show disk usage",
+            "df -h",
+            "human-readable sizes",
+            Vec::new(),
+            ValidationReport {
+                syntax_ok: true,
+                shell: "bash".to_string(),
+                checked_binaries: vec!["df".to_string()],
+                runtime_ok: true,
+                ..ValidationReport::default()
+            },
+            Some(TurnEnvironment {
+                cwd: Some("/srv/app".to_string()),
+                ..TurnEnvironment::default()
+            }),
+        );
+        session
+    }
+
+    #[test]
+    fn renders_markdown_runbook() {
+        let markdown = export_session(&sample(), ExportFormat::Markdown).unwrap();
+        assert!(markdown.contains("## 1. show disk usage"));
+        assert!(markdown.contains("Run in `/srv/app`\n\n```bash\ndf -h\n```"));
+        assert!(markdown.contains("human-readable sizes"));
+        assert!(markdown.contains("_Validation: syntax ok (bash); commands found: df_"));
+    }
+
+    #[test]
+    fn renders_annotated_shell_script() {
+        let script = export_session(&sample(), ExportFormat::Shell).unwrap();
+        assert!(script.starts_with("#!/usr/bin/env bash\n"));
+        assert!(script.contains(
+            "# 1. show disk usage\n#    human-readable sizes\n#    in /srv/app\ndf -h\n"
+        ));
+    }
+
+    #[test]
+    fn uses_program_name_of_shell_path() {
+        let mut session = sample();
+        session.turns[0].validation.shell = "/bin/bash".to_string();
+        let markdown = export_session(&session, ExportFormat::Markdown).unwrap();
+        assert!(markdown.contains("```bash\ndf -h\n```"));
+        let script = export_session(&session, ExportFormat::Shell).unwrap();
+        assert!(script.starts_with("#!/usr/bin/env bash\n"));
+    }
+
+    #[test]
+    fn fences_commands_containing_backticks() {
+        assert_eq!(code_fence("echo `date`"), "```");
+        assert_eq!(code_fence("a ```` b"), "`````");
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::{Context, Result};
+use uuid::Uuid;
+
+use super::record::{SessionRecord, parse_session};
+use super::store::SessionStore;
+
+/// Outcome of `cg sessions import`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedSession {
+    pub uuid: String,
+    pub original_uuid: String,
+    /// Name that was dropped because another local session already uses it.
+    pub dropped_name: Option<String>,
+}
+
+impl ImportedSession {
+    pub fn remapped(&self) -> bool {
+        self.uuid != self.original_uuid
+    }
+}
+
+pub(super) fn import_session(store: &dyn SessionStore, content: &str) -> Result<ImportedSession> {
+    let (record, imported) = prepare_import(
+        content,
+        |uuid| store.load_session(uuid).is_ok(),
+        |name| store.session_named(name).ok().flatten().is_some(),
+    )?;
+    store.save_session(&record)?;
+    Ok(imported)
+}
+
+/// Parses an exported session and gives it a fresh UUID if it collides with (or is not)
+/// a valid local UUID, dropping its name if the name is already taken.
+fn prepare_import(
+    content: &str,
+    uuid_exists: impl Fn(&str) -> bool,
+    name_taken: impl Fn(&str) -> bool,
+) -> Result<(SessionRecord, ImportedSession)> {
+    let (mut record, _) =
+        parse_session(content).context("import file is not an exported session")?;
+
+    let original_uuid = record.uuid.clone();
+    if Uuid::parse_str(&record.uuid).is_err() || uuid_exists(&record.uuid) {
+        record.uuid = Uuid::new_v4().to_string();
+    }
+    let dropped_name = match record.name.as_deref() {
+        Some(name) if name_taken(name) => record.name.take(),
+        _ => None,
+    };
+    let imported = ImportedSession {
+        uuid: record.uuid.clone(),
+        original_uuid,
+        dropped_name,
+    };
+    Ok((record, imported))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ProviderKind;
+    use crate::session::SESSION_SCHEMA_VERSION;
+
+    fn exported(name: Option<&str>) -> (SessionRecord, String) {
+        let mut record = SessionRecord::new(ProviderKind::Claude, "claude-sonnet-4-5");
+        record.name = name.map(str::to_string);
+        let content = serde_json::to_string(&record).unwrap();
+        (record, content)
+    }
+
+    #[test]
+    fn keeps_uuid_without_collision() {
+        let (record, content) = exported(Some("deploy"));
+        let (imported, outcome) = prepare_import(&content, |_| false, |_| false).unwrap();
+        assert_eq!(imported.uuid, record.uuid);
+        assert_eq!(imported.name.as_deref(), Some("deploy"));
+        assert!(!outcome.remapped());
+    }
+
+    #[test]
+    fn remaps_colliding_uuid_and_drops_taken_name() {
+        let (record, content) = exported(Some("deploy"));
+        let (imported, outcome) = prepare_import(&content, |_| true, |_| true).unwrap();
+        assert_ne!(imported.uuid, record.uuid);
+        assert!(outcome.remapped());
+        assert_eq!(outcome.dropped_name.as_deref(), Some("deploy"));
+        assert!(imported.name.is_none());
+    }
+
+    #[test]
+    fn rejects_newer_schema_versions() {
+        let (_, content) = exported(None);
+        let content = content.replacen(
+            &format!("\"schema_version\":{}", SESSION_SCHEMA_VERSION),
+            "\"schema_version\":99",
+            1,
+        );
+        let err = prepare_import(&content, |_| false, |_| false).unwrap_err();
+        assert!(format!("{err:#}").contains("newer"));
+    }
+}
@@ -1,20 +1,79 @@
-mod recent;
+mod environment;
+mod export;
+mod import;
 mod record;
 mod store;
 mod time;
 
 use anyhow::Result;
 
-pub use record::{SessionRecord, SessionTurn};
+pub use environment::TurnEnvironment;
+pub use export::{ExportFormat, export_session};
+pub use import::ImportedSession;
+pub use record::{SESSION_SCHEMA_VERSION, SessionParent, SessionRecord, SessionTurn};
+pub use store::{IndexedTurn, SessionStore, SessionStoreKind, StoreCheck};
+pub use time::{format_timestamp, now_unix};
 
-pub fn load_session(uuid: &str) -> Result<SessionRecord> {
-    store::load_session(uuid)
+/// Loads a session by full UUID, `last`, session name or unique UUID prefix.
+pub fn load_session(selector: &str) -> Result<SessionRecord> {
+    store::active_store()?.load_session(selector)
 }
 
 pub fn save_session(session: &SessionRecord) -> Result<()> {
-    store::save_session(session)
+    store::active_store()?.save_session(session)
 }
 
-pub fn list_recent_commands(limit: usize) -> Result<Vec<String>> {
-    recent::list_recent_commands(limit)
+/// Distinct recent commands, with those generated in `project` first.
+pub fn list_recent_commands(limit: usize, project: Option<&str>) -> Result<Vec<String>> {
+    store::active_store()?.list_recent_commands(limit, project)
+}
+
+pub fn search_turns(query: &str) -> Result<Vec<IndexedTurn>> {
+    store::active_store()?.search_turns(query)
+}
+
+/// Rebuilds the session search index and returns the number of indexed turns.
+pub fn rebuild_index() -> Result<usize> {
+    store::active_store()?.rebuild_index()
+}
+
+pub fn list_sessions() -> Result<Vec<SessionRecord>> {
+    store::active_store()?.list_sessions()
+}
+
+pub fn delete_session(uuid: &str) -> Result<()> {
+    store::active_store()?.delete_session(uuid)
+}
+
+/// Validates and assigns a human-readable name to `session` and saves it.
+pub fn rename_session(session: &mut SessionRecord, name: &str) -> Result<()> {
+    let name = name.trim();
+    let store = store::active_store()?;
+    store::validate_session_name(store.as_ref(), name, &session.uuid)?;
+    session.name = Some(name.to_string());
+    store.save_session(session)
+}
+
+/// Reads every stored session of the configured backend without skipping failures.
+/// Returns the store location and what was found.
+pub fn check_store() -> Result<(String, StoreCheck)> {
+    let store = store::active_store()?;
+    Ok((store.describe(), store.check()?))
+}
+
+/// Imports a session exported with `cg sessions export --format json`.
+pub fn import_session(content: &str) -> Result<ImportedSession> {
+    import::import_session(store::active_store()?.as_ref(), content)
+}
+
+/// Copies every JSON session file into the SQLite database. Returns the number of
+/// imported sessions and the database location.
+pub fn migrate_json_to_sqlite() -> Result<(usize, String)> {
+    let source = store::JsonSessionStore;
+    let target = store::SqliteSessionStore::open_default()?;
+    let sessions = source.list_sessions()?;
+    for session in &sessions {
+        target.save_session(session)?;
+    }
+    Ok((sessions.len(), target.describe()))
 }
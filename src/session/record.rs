@@ -1,9 +1,13 @@
+use anyhow::{Context, Result, anyhow};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
+use super::environment::TurnEnvironment;
 use super::time::now_unix;
 use crate::llm::CommandExplanationItem;
 use crate::model::ProviderKind;
+use crate::schema::Schema;
 use crate::validation::ValidationReport;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,37 +15,147 @@ pub struct SessionTurn {
     pub timestamp: i64,
     pub user_input: String,
     pub command: String,
-    #[serde(default)]
     pub reason: String,
-    #[serde(default)]
     pub explanations: Vec<CommandExplanationItem>,
     pub validation: ValidationReport,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<TurnEnvironment>,
 }
 
+/// Where a forked session branched off: the parent session and how many of its turns
+/// were copied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionParent {
+    pub uuid: String,
+    pub turn_index: usize,
+}
+
+/// Layout of session JSON. Every stored or imported record is upgraded through these
+/// migrations before it is deserialized, so changing a field means adding a migration
+/// rather than a `#[serde(default)]` or alias.
+pub(super) const SESSION_SCHEMA: Schema = Schema {
+    name: "session",
+    version_field: "schema_version",
+    migrations: &[fill_turn_defaults, fill_validation_checks],
+};
+
+/// Version written by this build; records newer than this are refused.
+pub const SESSION_SCHEMA_VERSION: u32 = SESSION_SCHEMA.current_version();
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionRecord {
+    pub schema_version: u32,
     pub uuid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
     pub provider: String,
     pub model: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<SessionParent>,
     pub turns: Vec<SessionTurn>,
 }
 
+/// Version 1 -> 2: early builds omitted `turns`, and a turn's `reason` and `explanations`
+/// when they were empty.
+fn fill_turn_defaults(record: &mut Map<String, Value>) -> Result<()> {
+    let turns = record
+        .entry("turns")
+        .or_insert_with(|| Value::Array(Vec::new()))
+        .as_array_mut()
+        .ok_or_else(|| anyhow!("'turns' is not an array"))?;
+    for turn in turns {
+        let turn = turn
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("turn is not an object"))?;
+        turn.entry("reason").or_insert_with(|| Value::from(""));
+        turn.entry("explanations")
+            .or_insert_with(|| Value::Array(Vec::new()));
+    }
+    Ok(())
+}
+
+/// Version 2 -> 3: validation reports gained the lists of the policy, shellcheck, option
+/// and input file checks.
+fn fill_validation_checks(record: &mut Map<String, Value>) -> Result<()> {
+    let turns = record
+        .get_mut("turns")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| anyhow!("'turns' is not an array"))?;
+    for turn in turns {
+        let validation = turn
+            .get_mut("validation")
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| anyhow!("turn has no validation report"))?;
+        for field in [
+            "policy_violations",
+            "shellcheck_findings",
+            "unknown_flags",
+            "missing_paths",
+        ] {
+            validation
+                .entry(field)
+                .or_insert_with(|| Value::Array(Vec::new()));
+        }
+    }
+    Ok(())
+}
+
+/// Parses stored session JSON, upgrading it to `SESSION_SCHEMA_VERSION` first. Returns
+/// the record and the schema version it was stored with.
+pub(super) fn parse_session(content: &str) -> Result<(SessionRecord, u32)> {
+    let mut value: Value = serde_json::from_str(content).context("invalid JSON")?;
+    let stored_version = SESSION_SCHEMA.upgrade(&mut value)?;
+    let record = serde_json::from_value(value).context("not a session record")?;
+    Ok((record, stored_version))
+}
+
 impl SessionRecord {
     pub fn new(provider: ProviderKind, model: impl Into<String>) -> Self {
         let now = now_unix();
         Self {
+            schema_version: SESSION_SCHEMA_VERSION,
             uuid: Uuid::new_v4().to_string(),
+            name: None,
             created_at: now,
             updated_at: now,
             provider: provider.as_str().to_string(),
             model: model.into(),
+            parent: None,
             turns: Vec::new(),
         }
     }
 
+    /// Creates a new session holding the first `turns` turns (all of them when `None`),
+    /// linked back to this one. The original session is left untouched.
+    pub fn fork(&self, turns: Option<usize>) -> Result<Self> {
+        let keep = turns.unwrap_or(self.turns.len());
+        if keep > self.turns.len() {
+            return Err(anyhow!(
+                "cannot fork at turn {}: session {} has {} turn(s)",
+                keep,
+                self.uuid,
+                self.turns.len()
+            ));
+        }
+        let now = now_unix();
+        Ok(Self {
+            schema_version: SESSION_SCHEMA_VERSION,
+            uuid: Uuid::new_v4().to_string(),
+            name: None,
+            created_at: now,
+            updated_at: now,
+            provider: self.provider.clone(),
+            model: self.model.clone(),
+            parent: Some(SessionParent {
+                uuid: self.uuid.clone(),
+                turn_index: keep,
+            }),
+            turns: self.turns[..keep].to_vec(),
+        })
+    }
+
     pub fn push_turn(
         &mut self,
         user_input: impl Into<String>,
@@ -49,6 +163,7 @@ impl SessionRecord {
         reason: impl Into<String>,
         explanations: Vec<CommandExplanationItem>,
         validation: ValidationReport,
+        environment: Option<TurnEnvironment>,
     ) {
         let now = now_unix();
         self.updated_at = now;
@@ -59,6 +174,7 @@ impl SessionRecord {
             reason: reason.into(),
             explanations,
             validation,
+            environment,
         });
     }
 
@@ -74,3 +190,63 @@ impl SessionRecord {
         turns
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fork_copies_turns_up_to_the_fork_point() {
+        let mut session = SessionRecord::new(ProviderKind::OpenAI, "gpt-5.2");
+        for command in ["ls", "pwd", "date"] {
+            session.push_turn(
+                command,
+                command,
+                "",
+                Vec::new(),
+                ValidationReport::default(),
+                None,
+            );
+        }
+        let fork = session.fork(Some(2)).unwrap();
+        assert_ne!(fork.uuid, session.uuid);
+        assert_eq!(fork.turns.len(), 2);
+        assert_eq!(
+            fork.parent,
+            Some(SessionParent {
+                uuid: session.uuid.clone(),
+                turn_index: 2
+            })
+        );
+        assert_eq!(session.turns.len(), 3);
+        assert_eq!(session.fork(None).unwrap().turns.len(), 3);
+        assert!(session.fork(Some(4)).is_err());
+    }
+
+    #[test]
+    fn upgrades_unversioned_records() {
+        let legacy = r#"{
+            "uuid": "e13d",
+            "created_at": 1,
+            "updated_at": 2,
+            "provider": "openai",
+            "model": "gpt-5.2",
+            "turns": [{
+                "timestamp": 2,
+                "user_input": "list files",
+                "command": "ls",
+                "validation": { "syntax_ok": true, "shell": "bash" }
+            }]
+        }"#;
+        let (record, stored_version) = parse_session(legacy).unwrap();
+        assert_eq!(stored_version, 1);
+        assert_eq!(record.schema_version, SESSION_SCHEMA_VERSION);
+        assert_eq!(record.turns[0].reason, "");
+        assert!(record.turns[0].explanations.is_empty());
+        assert!(record.turns[0].validation.missing_paths.is_empty());
+        assert!(record.turns[0].validation.risk.is_none());
+
+        let current = serde_json::to_string(&record).unwrap();
+        assert_eq!(parse_session(&current).unwrap().1, SESSION_SCHEMA_VERSION);
+    }
+}
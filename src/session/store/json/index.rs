@@ -0,0 +1,252 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::paths;
+use crate::session::record::SessionRecord;
+use crate::session::store::common_prefix_len;
+use crate::storage;
+
+const INDEX_FILE: &str = ".index.jsonl";
+
+/// One indexed turn, as returned by recent-command lookups and searches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedTurn {
+    pub session: String,
+    pub turn: usize,
+    pub timestamp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Git root (or working directory outside a repository) the turn was generated in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    pub user_input: String,
+    pub command: String,
+}
+
+/// A line of the append-only index. `Truncate` drops every indexed turn of `session`
+/// at or after `len`, which covers `/undo`, rewritten turns and deleted sessions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum IndexLine {
+    Turn(IndexedTurn),
+    Truncate { session: String, len: usize },
+}
+
+fn index_path() -> PathBuf {
+    paths::sessions_dir().join(INDEX_FILE)
+}
+
+/// Returns every live indexed turn, rebuilding the index from session files if it is missing.
+pub(super) fn load() -> Result<Vec<IndexedTurn>> {
+    let path = index_path();
+    if !path.exists() {
+        let _lock = super::lock_store()?;
+        if !path.exists() {
+            rebuild()?;
+        }
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read session index: {}", path.display()))?;
+    let (turns, corrupt) = replay(&content);
+    if corrupt > 0 {
+        eprintln!(
+            "warning: skipped {} unreadable line(s) in {} (run `cg sessions reindex` to rebuild it)",
+            corrupt,
+            path.display()
+        );
+    }
+    Ok(turns)
+}
+
+/// Describes unreadable index lines, for `cg doctor`. A missing index is not a problem;
+/// it is rebuilt on first use.
+pub(super) fn check() -> Result<Vec<String>> {
+    let path = index_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read session index: {}", path.display()))?;
+    let (_, corrupt) = replay(&content);
+    if corrupt == 0 {
+        return Ok(Vec::new());
+    }
+    Ok(vec![format!(
+        "{} unreadable line(s) in {} (run `cg sessions reindex`)",
+        corrupt,
+        path.display()
+    )])
+}
+
+/// Records the difference between the previously saved `old` turns and `session`
+/// (callers hold the store lock).
+/// A failed update removes the index so the next read rebuilds it instead of serving stale data.
+pub(super) fn record_save(old: Option<&SessionRecord>, session: &SessionRecord) {
+    if let Err(err) = try_record_save(old, session) {
+        eprintln!("warning: failed to update session index: {err}");
+        invalidate();
+    }
+}
+
+pub(super) fn record_delete(uuid: &str) {
+    let line = IndexLine::Truncate {
+        session: uuid.to_string(),
+        len: 0,
+    };
+    if let Err(err) = append(&[line]) {
+        eprintln!("warning: failed to update session index: {err}");
+        invalidate();
+    }
+}
+
+/// Rewrites the index from the session files on disk (callers hold the store lock) and returns the number of indexed turns.
+pub(super) fn rebuild() -> Result<usize> {
+    let mut lines = Vec::new();
+    for session in super::list_sessions()? {
+        lines.extend(turn_lines(&session, 0));
+    }
+    let count = lines.len();
+    let mut content = String::new();
+    for line in &lines {
+        content.push_str(&serde_json::to_string(line)?);
+        content.push('\n');
+    }
+    storage::write_atomic(&index_path(), content.as_bytes())?;
+    Ok(count)
+}
+
+fn try_record_save(old: Option<&SessionRecord>, session: &SessionRecord) -> Result<()> {
+    if !index_path().exists() {
+        // The session file is already on disk, so a rebuild includes this save.
+        rebuild()?;
+        return Ok(());
+    }
+    let old_turns = old.map(|old| old.turns.as_slice()).unwrap_or_default();
+    let common = common_prefix_len(old_turns, &session.turns);
+    let mut lines = Vec::new();
+    if old_turns.len() > common {
+        lines.push(IndexLine::Truncate {
+            session: session.uuid.clone(),
+            len: common,
+        });
+    }
+    lines.extend(turn_lines(session, common));
+    append(&lines)
+}
+
+fn append(lines: &[IndexLine]) -> Result<()> {
+    if lines.is_empty() {
+        return Ok(());
+    }
+    let mut content = String::new();
+    for line in lines {
+        content.push_str(&serde_json::to_string(line)?);
+        content.push('\n');
+    }
+    let path = index_path();
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("failed to open session index: {}", path.display()))?;
+    file.write_all(content.as_bytes())
+        .with_context(|| format!("failed to append to session index: {}", path.display()))
+}
+
+fn invalidate() {
+    let _ = fs::remove_file(index_path());
+}
+
+fn turn_lines(session: &SessionRecord, start: usize) -> Vec<IndexLine> {
+    session
+        .turns
+        .iter()
+        .enumerate()
+        .skip(start)
+        .map(|(turn, item)| {
+            IndexLine::Turn(IndexedTurn {
+                session: session.uuid.clone(),
+                turn,
+                timestamp: item.timestamp,
+                cwd: item.environment.as_ref().and_then(|env| env.cwd.clone()),
+                project: item
+                    .environment
+                    .as_ref()
+                    .and_then(|env| env.project())
+                    .map(str::to_string),
+                user_input: item.user_input.clone(),
+                command: item.command.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Applies the index log in order. Unparseable lines (e.g. a torn write after a crash)
+/// are skipped and counted.
+fn replay(content: &str) -> (Vec<IndexedTurn>, usize) {
+    let mut sessions: HashMap<String, Vec<IndexedTurn>> = HashMap::new();
+    let mut corrupt = 0;
+    for line in content.lines() {
+        let Ok(line) = serde_json::from_str::<IndexLine>(line) else {
+            corrupt += 1;
+            continue;
+        };
+        match line {
+            IndexLine::Turn(turn) => {
+                let turns = sessions.entry(turn.session.clone()).or_default();
+                turns.retain(|existing| existing.turn < turn.turn);
+                turns.push(turn);
+            }
+            IndexLine::Truncate { session, len } => {
+                if let Some(turns) = sessions.get_mut(&session) {
+                    turns.retain(|existing| existing.turn < len);
+                }
+            }
+        }
+    }
+    (sessions.into_values().flatten().collect(), corrupt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn_line(session: &str, turn: usize, command: &str) -> String {
+        serde_json::to_string(&IndexLine::Turn(IndexedTurn {
+            session: session.to_string(),
+            turn,
+            timestamp: turn as i64,
+            cwd: None,
+            project: None,
+            user_input: format!("request {turn}"),
+            command: command.to_string(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn replay_applies_truncate_tombstones() {
+        let content = [
+            turn_line("a", 0, "ls"),
+            turn_line("a", 1, "pwd"),
+            turn_line("b", 0, "date"),
+            r#"{"op":"truncate","session":"a","len":1}"#.to_string(),
+            turn_line("a", 1, "whoami"),
+            r#"{"op":"truncate","session":"b","len":0}"#.to_string(),
+            "{\"op\":\"turn\",\"sess".to_string(),
+        ]
+        .join("\n");
+        let (turns, corrupt) = replay(&content);
+        assert_eq!(corrupt, 1);
+        let mut commands = turns
+            .into_iter()
+            .map(|turn| turn.command)
+            .collect::<Vec<_>>();
+        commands.sort();
+        assert_eq!(commands, vec!["ls", "whoami"]);
+    }
+}
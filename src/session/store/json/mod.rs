@@ -0,0 +1,188 @@
+mod index;
+
+use anyhow::{Context, Result, anyhow};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{LAST_SESSION, SessionStore, StoreCheck, select_session, validate_selector};
+use crate::paths;
+use crate::session::record::{SESSION_SCHEMA_VERSION, SessionRecord, parse_session};
+use crate::storage::{self, FileLock};
+
+pub use index::IndexedTurn;
+
+/// One pretty-printed `<uuid>.json` file per session under `sessions_dir`, plus an
+/// append-only turn index for lookups that would otherwise read every file.
+pub struct JsonSessionStore;
+
+impl SessionStore for JsonSessionStore {
+    fn describe(&self) -> String {
+        paths::sessions_dir().display().to_string()
+    }
+
+    fn load_session(&self, selector: &str) -> Result<SessionRecord> {
+        let selector = validate_selector(selector)?;
+        if selector != LAST_SESSION {
+            let path = session_path(selector);
+            if path.exists() {
+                return read_session(&path);
+            }
+        }
+        let sessions = list_sessions()?;
+        select_session(&sessions, selector).cloned()
+    }
+
+    fn save_session(&self, session: &SessionRecord) -> Result<()> {
+        let _lock = lock_store()?;
+        let path = session_path(&session.uuid);
+        let previous = if path.exists() {
+            read_session(&path).ok()
+        } else {
+            None
+        };
+        let content = serde_json::to_string_pretty(session)?;
+        storage::write_atomic(&path, content.as_bytes())?;
+        index::record_save(previous.as_ref(), session);
+        Ok(())
+    }
+
+    fn list_sessions(&self) -> Result<Vec<SessionRecord>> {
+        list_sessions()
+    }
+
+    fn delete_session(&self, uuid: &str) -> Result<()> {
+        let uuid = validate_selector(uuid)?;
+        let _lock = lock_store()?;
+        let path = session_path(uuid);
+        if !path.exists() {
+            return Err(anyhow!("session '{}' not found", uuid));
+        }
+        fs::remove_file(&path)
+            .with_context(|| format!("failed to delete session file: {}", path.display()))?;
+        index::record_delete(uuid);
+        Ok(())
+    }
+
+    fn list_recent_commands(&self, limit: usize, project: Option<&str>) -> Result<Vec<String>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut items = index::load()?;
+        items.retain(|item| !item.command.trim().is_empty());
+        items.sort_by_key(|item| {
+            let same_project = project.is_some() && item.project.as_deref() == project;
+            std::cmp::Reverse((same_project, item.timestamp))
+        });
+
+        let mut deduped = Vec::new();
+        let mut seen = HashSet::new();
+        for item in items {
+            if seen.insert(item.command.clone()) {
+                deduped.push(item.command);
+                if deduped.len() >= limit {
+                    break;
+                }
+            }
+        }
+        Ok(deduped)
+    }
+
+    fn search_turns(&self, query: &str) -> Result<Vec<IndexedTurn>> {
+        let needle = query.trim().to_lowercase();
+        let mut matches = index::load()?;
+        matches.retain(|item| {
+            item.user_input.to_lowercase().contains(&needle)
+                || item.command.to_lowercase().contains(&needle)
+        });
+        matches.sort_by_key(|item| std::cmp::Reverse((item.timestamp, item.turn)));
+        Ok(matches)
+    }
+
+    fn rebuild_index(&self) -> Result<usize> {
+        let _lock = lock_store()?;
+        index::rebuild()
+    }
+
+    fn session_named(&self, name: &str) -> Result<Option<String>> {
+        Ok(list_sessions()?
+            .into_iter()
+            .find(|session| session.name.as_deref() == Some(name))
+            .map(|session| session.uuid))
+    }
+
+    fn check(&self) -> Result<StoreCheck> {
+        let mut check = StoreCheck::default();
+        for path in session_files()? {
+            let location = path.display().to_string();
+            match read_versioned(&path) {
+                Ok((_, version)) => {
+                    check.readable += 1;
+                    if version < SESSION_SCHEMA_VERSION {
+                        check.outdated.push(location);
+                    }
+                }
+                Err(err) => check.unreadable.push((location, format!("{err:#}"))),
+            }
+        }
+        check.index_problems = index::check()?;
+        Ok(check)
+    }
+}
+
+fn read_session(path: &Path) -> Result<SessionRecord> {
+    read_versioned(path)
+        .map(|(session, _)| session)
+        .with_context(|| format!("failed to load session file: {}", path.display()))
+}
+
+/// Reads a session file and the schema version it is stored with.
+fn read_versioned(path: &Path) -> Result<(SessionRecord, u32)> {
+    parse_session(&fs::read_to_string(path)?)
+}
+
+/// Reads every parseable session file, most recently updated first.
+fn list_sessions() -> Result<Vec<SessionRecord>> {
+    let mut sessions = Vec::new();
+    for path in session_files()? {
+        match read_session(&path) {
+            Ok(session) => sessions.push(session),
+            Err(err) => {
+                eprintln!("warning: skipping corrupt session file (see `cg doctor`): {err:#}")
+            }
+        }
+    }
+    sessions.sort_by_key(|session| std::cmp::Reverse(session.updated_at));
+    Ok(sessions)
+}
+
+fn session_files() -> Result<Vec<PathBuf>> {
+    let dir = paths::sessions_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in fs::read_dir(&dir)
+        .with_context(|| format!("failed to read sessions directory: {}", dir.display()))?
+    {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Serializes writers of session files and the index across `cg` processes.
+fn lock_store() -> Result<FileLock> {
+    storage::lock_exclusive(&paths::sessions_dir().join(".store"))
+}
+
+fn session_path(uuid: &str) -> PathBuf {
+    paths::sessions_dir().join(format!("{}.json", uuid))
+}
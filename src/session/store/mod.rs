@@ -0,0 +1,208 @@
+mod json;
+mod sqlite;
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use super::record::{SessionRecord, SessionTurn};
+use crate::config;
+
+pub use json::IndexedTurn;
+pub(super) use json::JsonSessionStore;
+pub(super) use sqlite::SqliteSessionStore;
+
+pub(super) const LAST_SESSION: &str = "last";
+
+/// Session storage backend, selected with `"sessionStore"` in `config.json`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionStoreKind {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+/// Health of a session store, reported by `cg doctor`.
+#[derive(Debug, Default)]
+pub struct StoreCheck {
+    pub readable: usize,
+    /// Sessions stored with an older schema; they are upgraded in memory when loaded and
+    /// rewritten on the next save.
+    pub outdated: Vec<String>,
+    /// Sessions that cannot be read, with the reason.
+    pub unreadable: Vec<(String, String)>,
+    pub index_problems: Vec<String>,
+}
+
+pub trait SessionStore {
+    /// Human-readable location, e.g. for `cg sessions migrate` output.
+    fn describe(&self) -> String;
+
+    /// Loads a session by full UUID, `last`, session name or unique UUID prefix.
+    fn load_session(&self, selector: &str) -> Result<SessionRecord>;
+
+    fn save_session(&self, session: &SessionRecord) -> Result<()>;
+
+    /// Every stored session, most recently updated first.
+    fn list_sessions(&self) -> Result<Vec<SessionRecord>>;
+
+    fn delete_session(&self, uuid: &str) -> Result<()>;
+
+    /// Distinct generated commands across all sessions, newest first. Commands generated
+    /// in `project` (see `TurnEnvironment::project`) come before all others.
+    fn list_recent_commands(&self, limit: usize, project: Option<&str>) -> Result<Vec<String>>;
+
+    /// Turns whose request or command matches `query`, newest first.
+    fn search_turns(&self, query: &str) -> Result<Vec<IndexedTurn>>;
+
+    /// Rebuilds the search index and returns the number of indexed turns.
+    fn rebuild_index(&self) -> Result<usize>;
+
+    /// UUID of the session currently called `name`, if any.
+    fn session_named(&self, name: &str) -> Result<Option<String>>;
+
+    /// Reads every stored session without skipping failures, for `cg doctor`.
+    fn check(&self) -> Result<StoreCheck>;
+}
+
+pub(super) fn open_store(kind: SessionStoreKind) -> Result<Box<dyn SessionStore>> {
+    match kind {
+        SessionStoreKind::Json => Ok(Box::new(JsonSessionStore)),
+        SessionStoreKind::Sqlite => Ok(Box::new(SqliteSessionStore::open_default()?)),
+    }
+}
+
+pub(super) fn active_store() -> Result<Box<dyn SessionStore>> {
+    open_store(config::load()?.session_store)
+}
+
+pub(super) fn validate_selector(selector: &str) -> Result<&str> {
+    let selector = selector.trim();
+    if selector.is_empty() || selector.contains(['/', '\\']) || selector.starts_with('.') {
+        return Err(anyhow!("invalid session id '{}'", selector));
+    }
+    Ok(selector)
+}
+
+/// Number of leading turns `new` shares with the previously saved `old` turns; everything
+/// after it has to be (re)indexed.
+pub(super) fn common_prefix_len(old: &[SessionTurn], new: &[SessionTurn]) -> usize {
+    old.iter()
+        .zip(new)
+        .take_while(|(left, right)| {
+            left.timestamp == right.timestamp
+                && left.user_input == right.user_input
+                && left.command == right.command
+        })
+        .count()
+}
+
+/// Resolves `selector` against `sessions` (sorted newest first): `last`, then an exact
+/// name, then a UUID prefix that must match exactly one session.
+pub(super) fn select_session<'a>(
+    sessions: &'a [SessionRecord],
+    selector: &str,
+) -> Result<&'a SessionRecord> {
+    if selector == LAST_SESSION {
+        return sessions
+            .first()
+            .ok_or_else(|| anyhow!("no sessions to resume"));
+    }
+    let named = sessions
+        .iter()
+        .filter(|session| session.name.as_deref() == Some(selector))
+        .collect::<Vec<_>>();
+    if let [session] = named.as_slice() {
+        return Ok(session);
+    }
+    let matches = if named.is_empty() {
+        sessions
+            .iter()
+            .filter(|session| session.uuid.starts_with(selector))
+            .collect::<Vec<_>>()
+    } else {
+        named
+    };
+    match matches.as_slice() {
+        [] => Err(anyhow!("session '{}' not found", selector)),
+        [session] => Ok(session),
+        candidates => Err(anyhow!(
+            "session '{}' is ambiguous; it matches: {}",
+            selector,
+            candidates
+                .iter()
+                .map(|session| session.uuid.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Checks that `name` can label the session `uuid`: it must not be reserved, look like a
+/// path, or already belong to another session.
+pub(super) fn validate_session_name(
+    store: &dyn SessionStore,
+    name: &str,
+    uuid: &str,
+) -> Result<()> {
+    if name.is_empty() {
+        return Err(anyhow!("session name is empty"));
+    }
+    if name == LAST_SESSION {
+        return Err(anyhow!(
+            "'{}' is reserved and cannot be a session name",
+            name
+        ));
+    }
+    if name.contains(['/', '\\']) || name.starts_with('.') || name.chars().any(char::is_whitespace)
+    {
+        return Err(anyhow!(
+            "session name '{}' must not contain whitespace or path separators",
+            name
+        ));
+    }
+    if let Some(other) = store.session_named(name)?
+        && other != uuid
+    {
+        return Err(anyhow!(
+            "session name '{}' is already used by {}",
+            name,
+            other
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ProviderKind;
+
+    fn session(uuid: &str, name: Option<&str>) -> SessionRecord {
+        let mut session = SessionRecord::new(ProviderKind::OpenAI, "gpt-5.2");
+        session.uuid = uuid.to_string();
+        session.name = name.map(str::to_string);
+        session
+    }
+
+    #[test]
+    fn selects_last_name_and_unique_prefix() {
+        let sessions = vec![
+            session("abc12", None),
+            session("abd34", Some("deploy")),
+            session("ffe56", None),
+        ];
+        assert_eq!(select_session(&sessions, "last").unwrap().uuid, "abc12");
+        assert_eq!(select_session(&sessions, "deploy").unwrap().uuid, "abd34");
+        assert_eq!(select_session(&sessions, "ff").unwrap().uuid, "ffe56");
+        assert!(select_session(&sessions, "zz").is_err());
+    }
+
+    #[test]
+    fn rejects_ambiguous_prefix() {
+        let sessions = vec![session("abc12", None), session("abd34", None)];
+        let err = select_session(&sessions, "ab").unwrap_err().to_string();
+        assert!(err.contains("ambiguous"));
+        assert!(err.contains("abc12") && err.contains("abd34"));
+    }
+}
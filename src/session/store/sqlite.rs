@@ -0,0 +1,383 @@
+use anyhow::{Context, Result, anyhow};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{
+    IndexedTurn, LAST_SESSION, SessionStore, StoreCheck, common_prefix_len, select_session,
+    validate_selector,
+};
+use crate::paths;
+use crate::session::record::{SESSION_SCHEMA_VERSION, SessionRecord, parse_session};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS sessions (
+    uuid TEXT PRIMARY KEY,
+    name TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    record TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS sessions_updated_at ON sessions(updated_at);
+CREATE INDEX IF NOT EXISTS sessions_name ON sessions(name);
+CREATE TABLE IF NOT EXISTS turns (
+    session TEXT NOT NULL,
+    turn INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    cwd TEXT,
+    project TEXT,
+    user_input TEXT NOT NULL,
+    command TEXT NOT NULL,
+    UNIQUE (session, turn)
+);
+CREATE INDEX IF NOT EXISTS turns_timestamp ON turns(timestamp);
+CREATE VIRTUAL TABLE IF NOT EXISTS turns_fts USING fts5(
+    user_input, command, content='turns', content_rowid='rowid'
+);
+CREATE TRIGGER IF NOT EXISTS turns_fts_insert AFTER INSERT ON turns BEGIN
+    INSERT INTO turns_fts(rowid, user_input, command)
+    VALUES (new.rowid, new.user_input, new.command);
+END;
+CREATE TRIGGER IF NOT EXISTS turns_fts_delete AFTER DELETE ON turns BEGIN
+    INSERT INTO turns_fts(turns_fts, rowid, user_input, command)
+    VALUES ('delete', old.rowid, old.user_input, old.command);
+END;
+";
+
+/// All sessions in a single SQLite database, with an FTS5 index over requests and commands.
+pub struct SqliteSessionStore {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl SqliteSessionStore {
+    pub fn open_default() -> Result<Self> {
+        Self::open(&paths::sessions_db_path())
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("failed to open session database: {}", path.display()))?;
+        Self::init(path.to_path_buf(), conn)
+    }
+
+    fn init(path: PathBuf, conn: Connection) -> Result<Self> {
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA).with_context(|| {
+            format!("failed to initialize session database: {}", path.display())
+        })?;
+        upgrade_schema(&conn)
+            .with_context(|| format!("failed to upgrade session database: {}", path.display()))?;
+        Ok(Self { path, conn })
+    }
+
+    fn load_exact(&self, uuid: &str) -> Result<Option<SessionRecord>> {
+        self.conn
+            .query_row(
+                "SELECT record FROM sessions WHERE uuid = ?1",
+                params![uuid],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .map(|record| parse_record(&record))
+            .transpose()
+    }
+
+    fn write_session(&self, session: &SessionRecord) -> Result<()> {
+        let previous = self.load_exact(&session.uuid)?;
+        let old_turns = previous
+            .as_ref()
+            .map(|old| old.turns.as_slice())
+            .unwrap_or_default();
+        let common = common_prefix_len(old_turns, &session.turns);
+
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO sessions (uuid, name, created_at, updated_at, record)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(uuid) DO UPDATE SET
+                name = excluded.name,
+                updated_at = excluded.updated_at,
+                record = excluded.record",
+            params![
+                session.uuid,
+                session.name,
+                session.created_at,
+                session.updated_at,
+                serde_json::to_string(session)?
+            ],
+        )?;
+        tx.execute(
+            "DELETE FROM turns WHERE session = ?1 AND turn >= ?2",
+            params![session.uuid, common as i64],
+        )?;
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO turns (session, turn, timestamp, cwd, project, user_input, command)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for (index, turn) in session.turns.iter().enumerate().skip(common) {
+                let environment = turn.environment.as_ref();
+                insert.execute(params![
+                    session.uuid,
+                    index as i64,
+                    turn.timestamp,
+                    environment.and_then(|env| env.cwd.as_deref()),
+                    environment.and_then(|env| env.project()),
+                    turn.user_input,
+                    turn.command
+                ])?;
+            }
+        }
+        tx.commit()
+            .with_context(|| format!("failed to save session '{}'", session.uuid))
+    }
+
+    fn query_records(&self, sql: &str, args: impl rusqlite::Params) -> Result<Vec<SessionRecord>> {
+        let mut statement = self.conn.prepare(sql)?;
+        let rows = statement.query_map(args, |row| row.get::<_, String>(0))?;
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(parse_record(&row?)?);
+        }
+        Ok(sessions)
+    }
+}
+
+impl SessionStore for SqliteSessionStore {
+    fn describe(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn load_session(&self, selector: &str) -> Result<SessionRecord> {
+        let selector = validate_selector(selector)?;
+        if selector == LAST_SESSION {
+            return self
+                .query_records(
+                    "SELECT record FROM sessions ORDER BY updated_at DESC LIMIT 1",
+                    [],
+                )?
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow!("no sessions to resume"));
+        }
+        if let Some(session) = self.load_exact(selector)? {
+            return Ok(session);
+        }
+        let candidates = self.query_records(
+            "SELECT record FROM sessions WHERE name = ?1 OR uuid LIKE ?2 ESCAPE '\\'
+             ORDER BY updated_at DESC",
+            params![selector, format!("{}%", escape_like(selector))],
+        )?;
+        select_session(&candidates, selector).cloned()
+    }
+
+    fn save_session(&self, session: &SessionRecord) -> Result<()> {
+        self.write_session(session)
+    }
+
+    fn list_sessions(&self) -> Result<Vec<SessionRecord>> {
+        self.query_records("SELECT record FROM sessions ORDER BY updated_at DESC", [])
+    }
+
+    fn delete_session(&self, uuid: &str) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM turns WHERE session = ?1", params![uuid])?;
+        let deleted = tx.execute("DELETE FROM sessions WHERE uuid = ?1", params![uuid])?;
+        if deleted == 0 {
+            return Err(anyhow!("session '{}' not found", uuid));
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn list_recent_commands(&self, limit: usize, project: Option<&str>) -> Result<Vec<String>> {
+        let mut statement = self.conn.prepare(
+            "SELECT command FROM turns WHERE trim(command) <> ''
+             GROUP BY command
+             ORDER BY MAX(COALESCE(project = ?2, 0)) DESC, MAX(timestamp) DESC
+             LIMIT ?1",
+        )?;
+        let rows = statement.query_map(params![limit as i64, project], |row| {
+            row.get::<_, String>(0)
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(Into::into)
+    }
+
+    fn search_turns(&self, query: &str) -> Result<Vec<IndexedTurn>> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let mut statement = self.conn.prepare(
+            "SELECT t.session, t.turn, t.timestamp, t.cwd, t.project, t.user_input, t.command
+             FROM turns_fts JOIN turns t ON t.rowid = turns_fts.rowid
+             WHERE turns_fts MATCH ?1
+             ORDER BY t.timestamp DESC, t.turn DESC",
+        )?;
+        let rows = statement.query_map(params![query], |row| {
+            Ok(IndexedTurn {
+                session: row.get(0)?,
+                turn: row.get::<_, i64>(1)? as usize,
+                timestamp: row.get(2)?,
+                cwd: row.get(3)?,
+                project: row.get(4)?,
+                user_input: row.get(5)?,
+                command: row.get(6)?,
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(Into::into)
+    }
+
+    fn rebuild_index(&self) -> Result<usize> {
+        self.conn
+            .execute("INSERT INTO turns_fts(turns_fts) VALUES ('rebuild')", [])?;
+        let count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM turns", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    fn session_named(&self, name: &str) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT uuid FROM sessions WHERE name = ?1 LIMIT 1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    fn check(&self) -> Result<StoreCheck> {
+        let mut check = StoreCheck::default();
+        let mut statement = self.conn.prepare("SELECT uuid, record FROM sessions")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (uuid, record) = row?;
+            let location = format!("{} (session {})", self.path.display(), uuid);
+            match parse_session(&record) {
+                Ok((_, version)) => {
+                    check.readable += 1;
+                    if version < SESSION_SCHEMA_VERSION {
+                        check.outdated.push(location);
+                    }
+                }
+                Err(err) => check.unreadable.push((location, format!("{err:#}"))),
+            }
+        }
+        if let Err(err) = self.conn.execute(
+            "INSERT INTO turns_fts(turns_fts) VALUES ('integrity-check')",
+            [],
+        ) {
+            check.index_problems.push(format!(
+                "full-text index is inconsistent ({err}); run `cg sessions reindex`"
+            ));
+        }
+        Ok(check)
+    }
+}
+
+/// Brings databases created by older builds up to `SCHEMA`, tracked with `user_version`.
+fn upgrade_schema(conn: &Connection) -> Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < 1 {
+        let has_project = conn
+            .prepare("SELECT 1 FROM pragma_table_info('turns') WHERE name = 'project'")?
+            .exists([])?;
+        if !has_project {
+            conn.execute("ALTER TABLE turns ADD COLUMN project TEXT", [])?;
+        }
+        conn.execute("PRAGMA user_version = 1", [])?;
+    }
+    Ok(())
+}
+
+fn parse_record(record: &str) -> Result<SessionRecord> {
+    parse_session(record)
+        .map(|(session, _)| session)
+        .context("failed to parse session record from database")
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Turns free text into an FTS5 query: every word must appear, matched as a prefix.
+fn fts_query(text: &str) -> Option<String> {
+    let terms = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ProviderKind;
+    use crate::validation::ValidationReport;
+
+    fn memory_store() -> SqliteSessionStore {
+        SqliteSessionStore::init(
+            PathBuf::from(":memory:"),
+            Connection::open_in_memory().unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn push(session: &mut SessionRecord, request: &str, command: &str) {
+        session.push_turn(
+            request,
+            command,
+            "",
+            Vec::new(),
+            ValidationReport::default(),
+            None,
+        );
+    }
+
+    #[test]
+    fn saves_searches_and_truncates_turns() {
+        let store = memory_store();
+        let mut session = SessionRecord::new(ProviderKind::OpenAI, "gpt-5.2");
+        push(&mut session, "list containers", "docker ps -a");
+        push(&mut session, "show disk usage", "df -h");
+        store.save_session(&session).unwrap();
+
+        let found = store.search_turns("dock").unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].command, "docker ps -a");
+        assert_eq!(store.list_recent_commands(10, None).unwrap().len(), 2);
+
+        session.turns.pop();
+        store.save_session(&session).unwrap();
+        assert!(store.search_turns("disk").unwrap().is_empty());
+        assert_eq!(store.rebuild_index().unwrap(), 1);
+
+        let prefix = &session.uuid[..8];
+        assert_eq!(store.load_session(prefix).unwrap().turns.len(), 1);
+        store.delete_session(&session.uuid).unwrap();
+        assert!(store.load_session("last").is_err());
+    }
+
+    #[test]
+    fn builds_prefix_fts_queries() {
+        assert_eq!(fts_query("docker  ps").unwrap(), "\"docker\"* \"ps\"*");
+        assert_eq!(fts_query("say \"hi\"").unwrap(), "\"say\"* \"\"\"hi\"\"\"*");
+        assert!(fts_query("   ").is_none());
+    }
+}
@@ -0,0 +1,282 @@
+/// Byte range into the parsed source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
+
+/// A sequence of and-or lists separated by `;`, `&` or newlines: a whole command line,
+/// or the body of a subshell, group, substitution or compound command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Script {
+    pub items: Vec<ListItem>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListItem {
+    pub and_or: AndOr,
+    pub separator: Option<Separator>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    Semicolon,
+    /// `&`: the list runs as a background job.
+    Background,
+    Newline,
+}
+
+/// Pipelines joined by `&&` and `||`, evaluated left to right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AndOr {
+    pub first: Pipeline,
+    pub rest: Vec<(AndOrOp, Pipeline)>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AndOrOp {
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    /// Prefixed with the `time` keyword.
+    pub timed: bool,
+    /// Prefixed with `!`.
+    pub negated: bool,
+    pub commands: Vec<Command>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Simple(SimpleCommand),
+    Compound {
+        body: CompoundCommand,
+        redirects: Vec<Redirect>,
+        span: Span,
+    },
+    FunctionDef {
+        name: String,
+        body: Box<Command>,
+        span: Span,
+    },
+}
+
+impl Command {
+    pub fn span(&self) -> Span {
+        match self {
+            Command::Simple(simple) => simple.span,
+            Command::Compound { span, .. } | Command::FunctionDef { span, .. } => *span,
+        }
+    }
+}
+
+/// Assignments, words and redirections of one command, in source order per kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimpleCommand {
+    pub assignments: Vec<Assignment>,
+    pub words: Vec<Word>,
+    pub redirects: Vec<Redirect>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub name: String,
+    /// `None` for bash array assignments such as `names=(a b)`.
+    pub value: Option<Word>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompoundCommand {
+    /// `( ... )`
+    Subshell(Script),
+    /// `{ ...; }`
+    Group(Script),
+    If {
+        /// `if`/`elif` conditions with their `then` bodies.
+        branches: Vec<(Script, Script)>,
+        else_body: Option<Script>,
+    },
+    While {
+        condition: Script,
+        body: Script,
+        /// `until` rather than `while`.
+        until: bool,
+    },
+    For {
+        variable: String,
+        /// `None` when the `in` list is omitted (iterates over `"$@"`).
+        items: Option<Vec<Word>>,
+        body: Script,
+        /// Bash `select` rather than `for`.
+        select: bool,
+    },
+    /// Bash `for (( init; test; step ))`, with the header kept as raw text.
+    ArithmeticFor {
+        header: String,
+        body: Script,
+    },
+    Case {
+        subject: Word,
+        arms: Vec<CaseArm>,
+    },
+    /// Bash `[[ ... ]]`; operators inside are plain words.
+    Conditional(Vec<Word>),
+    /// Bash `(( ... ))`, kept as raw text.
+    Arithmetic(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseArm {
+    pub patterns: Vec<Word>,
+    pub body: Script,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// Explicit file descriptor, as in `2>`.
+    pub fd: Option<u32>,
+    pub op: RedirectOp,
+    pub target: RedirectTarget,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectOp {
+    /// `<`
+    Input,
+    /// `>`
+    Output,
+    /// `>>`
+    Append,
+    /// `>|`
+    Clobber,
+    /// `<>`
+    ReadWrite,
+    /// `<&`
+    DupInput,
+    /// `>&`
+    DupOutput,
+    /// `&>`
+    OutputAll,
+    /// `&>>`
+    AppendAll,
+    /// `<<<`
+    HereString,
+    /// `<<` or `<<-`
+    HereDoc,
+}
+
+impl RedirectOp {
+    /// Whether the redirection may create or overwrite its target file.
+    pub fn writes(self) -> bool {
+        matches!(
+            self,
+            RedirectOp::Output
+                | RedirectOp::Append
+                | RedirectOp::Clobber
+                | RedirectOp::ReadWrite
+                | RedirectOp::OutputAll
+                | RedirectOp::AppendAll
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedirectTarget {
+    Word(Word),
+    HereDoc(HereDoc),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HereDoc {
+    pub delimiter: String,
+    /// The delimiter was quoted, so the body is not expanded.
+    pub quoted: bool,
+    /// `<<-`: leading tabs are stripped from body lines.
+    pub strip_tabs: bool,
+    pub body: String,
+    /// The body split into literal text and expansions; empty when `quoted`.
+    pub parts: Vec<WordPart>,
+    pub body_span: Span,
+}
+
+/// One shell word, split into the quoted, literal and expanded parts it is made of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    pub parts: Vec<WordPart>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordPart {
+    /// Unquoted text, with backslash escapes removed.
+    Literal(String),
+    /// `'...'` or `$'...'`
+    SingleQuoted(String),
+    DoubleQuoted(Vec<WordPart>),
+    /// `$name` or a special parameter such as `$?`; holds the text after `$`.
+    Parameter(String),
+    /// `${...}`: the text after `$`, and the parts inside the braces, whose expansions
+    /// run when the parameter is expanded (as in `${name:-$(cmd)}`).
+    BracedParameter {
+        text: String,
+        parts: Vec<WordPart>,
+    },
+    /// `$( ... )`
+    CommandSubstitution(Script),
+    /// `` `...` ``
+    Backtick(Script),
+    /// `$(( ... ))`, kept as raw text.
+    Arithmetic(String),
+    /// `<( ... )` or `>( ... )`
+    ProcessSubstitution {
+        input: bool,
+        script: Script,
+    },
+}
+
+impl Word {
+    /// The word's value after quote removal, or `None` if it contains an expansion whose
+    /// value is only known at run time. Globs, braces and tildes are kept as written.
+    pub fn static_value(&self) -> Option<String> {
+        let mut value = String::new();
+        if static_parts(&self.parts, &mut value) {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the word starts with an unquoted `~`, i.e. undergoes tilde expansion.
+    pub fn has_tilde_prefix(&self) -> bool {
+        matches!(self.parts.first(), Some(WordPart::Literal(text)) if text.starts_with('~'))
+    }
+}
+
+fn static_parts(parts: &[WordPart], value: &mut String) -> bool {
+    for part in parts {
+        match part {
+            WordPart::Literal(text) | WordPart::SingleQuoted(text) => value.push_str(text),
+            WordPart::DoubleQuoted(inner) => {
+                if !static_parts(inner, value) {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+    true
+}
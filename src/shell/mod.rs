@@ -0,0 +1,241 @@
+//! A parser for the POSIX shell / bash subset that generated commands use, producing an
+//! AST with byte spans into the source so that callers can both inspect and rewrite it.
+
+mod ast;
+mod parser;
+mod visit;
+
+pub use ast::{
+    AndOr, AndOrOp, Assignment, CaseArm, Command, CompoundCommand, HereDoc, ListItem, Pipeline,
+    Redirect, RedirectOp, RedirectTarget, Script, Separator, SimpleCommand, Span, Word, WordPart,
+};
+pub use parser::ParseError;
+pub use visit::{
+    for_each_pipeline, for_each_redirect, for_each_simple_command, for_each_simple_command_deep,
+    simple_commands,
+};
+
+pub fn parse(source: &str) -> Result<Script, ParseError> {
+    parser::Parser::new(source).parse_script()
+}
+
+/// Inserts each `(offset, text)` into `source`; offsets refer to the original string.
+pub fn apply_insertions(source: &str, mut insertions: Vec<(usize, String)>) -> String {
+    insertions.sort_by_key(|(offset, _)| *offset);
+    let mut out = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for (offset, text) in insertions {
+        out.push_str(&source[cursor..offset]);
+        out.push_str(&text);
+        cursor = offset;
+    }
+    out.push_str(&source[cursor..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heads(source: &str) -> Vec<String> {
+        simple_commands(&parse(source).unwrap())
+            .into_iter()
+            .filter_map(|command| command.words.first()?.static_value())
+            .collect()
+    }
+
+    #[test]
+    fn parses_lists_pipelines_and_compound_commands() {
+        assert_eq!(
+            heads("a && b || c; d | e & f"),
+            vec!["a", "b", "c", "d", "e", "f"]
+        );
+        assert_eq!(
+            heads("if test -d x; then (cd x && ls); elif true; then :; else { pwd; }; fi"),
+            vec!["test", "cd", "ls", "true", ":", "pwd"]
+        );
+        assert_eq!(
+            heads("for f in *.rs; do wc -l \"$f\"; done\nwhile read l; do echo $l; done < in"),
+            vec!["wc", "read", "echo"]
+        );
+        assert_eq!(
+            heads("case $1 in a|b) echo ab;; *) exit 1;; esac"),
+            vec!["echo", "exit"]
+        );
+        assert_eq!(heads("[[ $a < b ]] && f() { g; }"), vec!["g"]);
+    }
+
+    #[test]
+    fn parses_words_and_expansions() {
+        let script = parse(r#"FOO=1 echo "a $(date +%s)" 'b c' d\ e `pwd` $HOME"#).unwrap();
+        let command = simple_commands(&script)[0];
+        assert_eq!(command.assignments[0].name, "FOO");
+        let values = command
+            .words
+            .iter()
+            .map(Word::static_value)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                Some("echo".to_string()),
+                None,
+                Some("b c".to_string()),
+                Some("d e".to_string()),
+                None,
+                None
+            ]
+        );
+        let WordPart::DoubleQuoted(parts) = &command.words[1].parts[0] else {
+            panic!("expected a double-quoted part");
+        };
+        let WordPart::CommandSubstitution(inner) = &parts[1] else {
+            panic!("expected a command substitution");
+        };
+        assert_eq!(heads_of(inner), vec!["date"]);
+    }
+
+    fn heads_of(script: &Script) -> Vec<String> {
+        simple_commands(script)
+            .into_iter()
+            .filter_map(|command| command.words.first()?.static_value())
+            .collect()
+    }
+
+    #[test]
+    fn parses_redirections_and_heredocs() {
+        let source = "cat <<'EOF' | grep -v x 2>&1 >out.txt\nhello $name\nEOF\necho done";
+        let script = parse(source).unwrap();
+        let commands = simple_commands(&script);
+        assert_eq!(heads_of(&script), vec!["cat", "grep", "echo"]);
+        let RedirectTarget::HereDoc(heredoc) = &commands[0].redirects[0].target else {
+            panic!("expected a here-document");
+        };
+        assert_eq!(heredoc.body, "hello $name\n");
+        assert!(heredoc.quoted);
+        let ops = commands[1]
+            .redirects
+            .iter()
+            .map(|redirect| (redirect.fd, redirect.op))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ops,
+            vec![(Some(2), RedirectOp::DupOutput), (None, RedirectOp::Output)]
+        );
+        assert_eq!(
+            &source[commands[1].span.start..commands[1].span.end],
+            "grep -v x 2>&1 >out.txt"
+        );
+    }
+
+    #[test]
+    fn parses_bash_specific_forms() {
+        assert_eq!(heads("((cd a) && ls)"), vec!["cd", "ls"]);
+        assert_eq!(heads("echo $((cd a) && pwd)"), vec!["echo"]);
+        assert_eq!(heads("(( i += 1 )) && echo $(( i * 2 ))"), vec!["echo"]);
+        assert_eq!(
+            heads("select x in a b; do echo \"$x\"; break; done"),
+            vec!["echo", "break"]
+        );
+        assert_eq!(
+            heads("for ((i = 0; i < 3; i++)); do echo $i; done"),
+            vec!["echo"]
+        );
+        assert_eq!(heads("function f { g; }; f"), vec!["g", "f"]);
+        assert_eq!(
+            heads("case $x in a) echo a;& b) echo b;;& *) :;; esac"),
+            vec!["echo", "echo", ":"]
+        );
+        assert_eq!(heads("names=(a b) ls"), vec!["ls"]);
+        assert_eq!(heads("a[0]=1 a[$i+1]+=x ls"), vec!["ls"]);
+        assert_eq!(
+            heads("declare -A m=([a]=1); local -a xs=(1 2) n=3; ls"),
+            vec!["declare", "local", "ls"]
+        );
+        let script = parse("a[0]=1 ls a[1]=2").unwrap();
+        let command = simple_commands(&script)[0];
+        assert_eq!(command.assignments[0].name, "a");
+        assert_eq!(command.words[1].static_value().as_deref(), Some("a[1]=2"));
+        let script = parse("typeset -a xs=(\"a b\" c)").unwrap();
+        let words = &simple_commands(&script)[0].words;
+        assert_eq!(words[2].static_value().as_deref(), Some("xs=(\"a b\" c)"));
+        assert_eq!(heads("echo $'it\\'s'; ! grep -q x f"), vec!["echo", "grep"]);
+    }
+
+    #[test]
+    fn parses_time_prefix_and_bare_time() {
+        let script = parse("time -p sleep 1 | wc").unwrap();
+        assert!(script.items[0].and_or.first.timed);
+        assert_eq!(heads_of(&script), vec!["sleep", "wc"]);
+        for source in ["time", "time -p", "time; ls"] {
+            let script = parse(source).unwrap();
+            assert!(!script.items[0].and_or.first.timed, "{source}");
+            assert_eq!(heads_of(&script)[0], "time", "{source}");
+        }
+    }
+
+    #[test]
+    fn parses_heredoc_variants() {
+        let source = "cat <<A - <<-'B'\nfirst\nA\n\tsecond\n\tB\necho after";
+        let script = parse(source).unwrap();
+        let commands = simple_commands(&script);
+        let bodies = commands[0]
+            .redirects
+            .iter()
+            .filter_map(|redirect| match &redirect.target {
+                RedirectTarget::HereDoc(heredoc) => Some(heredoc.body.as_str()),
+                RedirectTarget::Word(_) => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(bodies, vec!["first\n", "second\n"]);
+        assert_eq!(heads_of(&script), vec!["cat", "echo"]);
+
+        let script = parse("cat <<EOF").unwrap();
+        let RedirectTarget::HereDoc(heredoc) = &simple_commands(&script)[0].redirects[0].target
+        else {
+            panic!("expected a here-document");
+        };
+        assert_eq!(heredoc.body, "");
+        assert_eq!(heads("cat <<EOF | grep x\nbody\n"), vec!["cat", "grep"]);
+    }
+
+    #[test]
+    fn parses_process_substitution_and_spans() {
+        let source = "diff <(sort a) <(sort b) && echo `uname -s`";
+        let script = parse(source).unwrap();
+        let mut deep = Vec::new();
+        for_each_simple_command_deep(&script, &mut |command| {
+            deep.push(&source[command.span.start..command.span.end]);
+        });
+        assert_eq!(
+            deep,
+            vec![
+                "diff <(sort a) <(sort b)",
+                "sort a",
+                "sort b",
+                "echo `uname -s`",
+                "uname -s"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        for source in [
+            "echo 'open",
+            "if true; then ls",
+            "ls |",
+            "a && ",
+            "echo )",
+            "fi",
+            "((cd a) && ls",
+            "select x in a; do echo",
+            "case x in a) echo",
+            "echo \"$(date\"",
+            "f() ls",
+        ] {
+            assert!(parse(source).is_err(), "{source}");
+        }
+        assert_eq!(parse("ls -l (").unwrap_err().offset, 6);
+    }
+}
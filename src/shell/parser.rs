@@ -0,0 +1,1263 @@
+use std::fmt;
+
+use super::ast::{
+    AndOr, AndOrOp, Assignment, CaseArm, Command, CompoundCommand, HereDoc, ListItem, Pipeline,
+    Redirect, RedirectOp, RedirectTarget, Script, Separator, SimpleCommand, Span, Word, WordPart,
+};
+
+/// Words that end a nested list; they are only reserved at command position.
+const CLOSING_WORDS: &[&str] = &[
+    "then", "elif", "else", "fi", "do", "done", "esac", "}", "in",
+];
+
+/// Redirection operators, longest first so that prefixes do not shadow them.
+const REDIRECT_OPS: &[(&str, RedirectOp)] = &[
+    ("&>>", RedirectOp::AppendAll),
+    ("<<<", RedirectOp::HereString),
+    ("<<-", RedirectOp::HereDoc),
+    ("&>", RedirectOp::OutputAll),
+    ("<<", RedirectOp::HereDoc),
+    ("<>", RedirectOp::ReadWrite),
+    ("<&", RedirectOp::DupInput),
+    (">>", RedirectOp::Append),
+    (">|", RedirectOp::Clobber),
+    (">&", RedirectOp::DupOutput),
+    ("<", RedirectOp::Input),
+    (">", RedirectOp::Output),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Byte offset into the source where parsing failed.
+    pub offset: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+type ParseResult<T> = Result<T, ParseError>;
+
+pub(super) struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    /// Added to every span, for bodies re-parsed out of backticks.
+    base: usize,
+    /// Set while here-document bodies follow the current line: the position of the
+    /// line's newline and where parsing resumes after the last body.
+    heredoc_skip: Option<(usize, usize)>,
+}
+
+impl<'a> Parser<'a> {
+    pub(super) fn new(src: &'a str) -> Self {
+        Self::with_base(src, 0)
+    }
+
+    fn with_base(src: &'a str, base: usize) -> Self {
+        Self {
+            src,
+            pos: 0,
+            base,
+            heredoc_skip: None,
+        }
+    }
+
+    pub(super) fn parse_script(mut self) -> ParseResult<Script> {
+        let script = self.parse_list(&[])?;
+        self.skip_linebreaks();
+        match self.peek() {
+            None => Ok(script),
+            Some(_) => Err(self.unexpected()),
+        }
+    }
+
+    // ---- lists and pipelines ----
+
+    fn parse_list(&mut self, terminators: &[&str]) -> ParseResult<Script> {
+        self.skip_linebreaks();
+        let start = self.pos;
+        let mut items = Vec::new();
+        loop {
+            self.skip_linebreaks();
+            if self.at_list_end(terminators) {
+                break;
+            }
+            let and_or = self.parse_and_or()?;
+            self.skip_blanks();
+            let separator = if self.rest().starts_with(";;") || self.rest().starts_with(";&") {
+                None
+            } else {
+                match self.peek() {
+                    Some(';') => {
+                        self.pos += 1;
+                        Some(Separator::Semicolon)
+                    }
+                    Some('&') => {
+                        self.pos += 1;
+                        Some(Separator::Background)
+                    }
+                    Some('\n') => {
+                        self.consume_newline();
+                        Some(Separator::Newline)
+                    }
+                    _ => None,
+                }
+            };
+            items.push(ListItem { and_or, separator });
+            if separator.is_none() {
+                break;
+            }
+        }
+        let end = items
+            .last()
+            .map(|item| item.and_or.span.end)
+            .unwrap_or(self.base + start);
+        Ok(Script {
+            items,
+            span: Span::new(self.base + start, end),
+        })
+    }
+
+    fn at_list_end(&self, terminators: &[&str]) -> bool {
+        let rest = self.rest();
+        rest.is_empty()
+            || rest.starts_with(')')
+            || rest.starts_with(";;")
+            || rest.starts_with(";&")
+            || self
+                .peek_reserved()
+                .is_some_and(|word| terminators.contains(&word))
+    }
+
+    fn parse_and_or(&mut self) -> ParseResult<AndOr> {
+        let start = self.pos;
+        let first = self.parse_pipeline()?;
+        let mut rest = Vec::new();
+        loop {
+            let before = self.pos;
+            self.skip_blanks();
+            let op = if self.rest().starts_with("&&") {
+                AndOrOp::And
+            } else if self.rest().starts_with("||") {
+                AndOrOp::Or
+            } else {
+                self.pos = before;
+                break;
+            };
+            self.pos += 2;
+            self.skip_linebreaks();
+            rest.push((op, self.parse_pipeline()?));
+        }
+        Ok(AndOr {
+            first,
+            rest,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_pipeline(&mut self) -> ParseResult<Pipeline> {
+        self.skip_blanks();
+        let start = self.pos;
+        let mut timed = self.peek_reserved() == Some("time");
+        if timed {
+            self.pos += "time".len();
+            self.skip_blanks();
+            if self.rest().starts_with("-p")
+                && (self.rest().len() == 2
+                    || self.rest()[2..].starts_with([' ', '\t', '\n', ';', '&', '|', ')']))
+            {
+                self.pos += 2;
+                self.skip_blanks();
+            }
+            // A bare `time` (or `time -p`) times nothing; it is parsed as a simple command.
+            if self.at_command_end() {
+                self.pos = start;
+                timed = false;
+            }
+        }
+        let negated = self.peek_reserved() == Some("!");
+        if negated {
+            self.pos += 1;
+        }
+        let mut commands = vec![self.parse_command()?];
+        loop {
+            let before = self.pos;
+            self.skip_blanks();
+            if self.rest().starts_with("||") || !self.rest().starts_with('|') {
+                self.pos = before;
+                break;
+            }
+            self.pos += if self.rest().starts_with("|&") { 2 } else { 1 };
+            self.skip_linebreaks();
+            commands.push(self.parse_command()?);
+        }
+        Ok(Pipeline {
+            timed,
+            negated,
+            commands,
+            span: self.span_from(start),
+        })
+    }
+
+    // ---- commands ----
+
+    fn parse_command(&mut self) -> ParseResult<Command> {
+        self.skip_blanks();
+        let start = self.pos;
+        let body = if self.rest().starts_with("((")
+            && let Some(expression) = self.try_read_arithmetic()
+        {
+            Some(CompoundCommand::Arithmetic(expression))
+        } else if self.peek() == Some('(') {
+            self.pos += 1;
+            let body = self.parse_list(&[])?;
+            self.expect_char(')')?;
+            Some(CompoundCommand::Subshell(body))
+        } else {
+            match self.peek_reserved() {
+                Some("{") => {
+                    self.pos += 1;
+                    let body = self.parse_list(&["}"])?;
+                    self.expect_reserved("}")?;
+                    Some(CompoundCommand::Group(body))
+                }
+                Some("if") => Some(self.parse_if()?),
+                Some(keyword @ ("while" | "until")) => {
+                    let until = keyword == "until";
+                    self.pos += keyword.len();
+                    let condition = self.parse_list(&["do"])?;
+                    let body = self.parse_do_group()?;
+                    Some(CompoundCommand::While {
+                        condition,
+                        body,
+                        until,
+                    })
+                }
+                Some(keyword @ ("for" | "select")) => Some(self.parse_for(keyword)?),
+                Some("case") => Some(self.parse_case()?),
+                Some("[[") => Some(self.parse_conditional()?),
+                Some("function") => return self.parse_function_keyword(start),
+                Some(word) if CLOSING_WORDS.contains(&word) && word != "in" => {
+                    return Err(self.error(format!("unexpected `{}`", word)));
+                }
+                _ => None,
+            }
+        };
+        match body {
+            Some(body) => {
+                let redirects = self.parse_trailing_redirects()?;
+                Ok(Command::Compound {
+                    body,
+                    redirects,
+                    span: self.span_from(start),
+                })
+            }
+            None => self.parse_simple_command(start),
+        }
+    }
+
+    fn parse_simple_command(&mut self, start: usize) -> ParseResult<Command> {
+        let mut assignments = Vec::new();
+        let mut words: Vec<Word> = Vec::new();
+        let mut redirects = Vec::new();
+        loop {
+            let before = self.pos;
+            self.skip_blanks();
+            if let Some(redirect) = self.try_parse_redirect()? {
+                redirects.push(redirect);
+                continue;
+            }
+            if self.at_command_end() {
+                self.pos = before;
+                break;
+            }
+            if self.peek() == Some('(') && !self.at_process_substitution() {
+                if words.len() == 1 && assignments.is_empty() && redirects.is_empty() {
+                    let name = words[0]
+                        .static_value()
+                        .ok_or_else(|| self.error("invalid function name"))?;
+                    return self.parse_function_body(start, name);
+                }
+                return Err(self.unexpected());
+            }
+            if words.is_empty()
+                && let Some(assignment) = self.try_parse_assignment()?
+            {
+                assignments.push(assignment);
+                continue;
+            }
+            if is_declaration(&words)
+                && let Some(word) = self.try_parse_array_argument()?
+            {
+                words.push(word);
+                continue;
+            }
+            match self.parse_word()? {
+                Some(word) => words.push(word),
+                None => return Err(self.unexpected()),
+            }
+        }
+        if assignments.is_empty() && words.is_empty() && redirects.is_empty() {
+            return Err(match self.peek() {
+                None => self.error("expected a command"),
+                Some(_) => self.unexpected(),
+            });
+        }
+        Ok(Command::Simple(SimpleCommand {
+            assignments,
+            words,
+            redirects,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn at_command_end(&self) -> bool {
+        match self.peek() {
+            None => true,
+            Some('\n' | ';' | '&' | '|' | ')') => true,
+            Some(_) => false,
+        }
+    }
+
+    fn parse_trailing_redirects(&mut self) -> ParseResult<Vec<Redirect>> {
+        let mut redirects = Vec::new();
+        loop {
+            let before = self.pos;
+            self.skip_blanks();
+            match self.try_parse_redirect()? {
+                Some(redirect) => redirects.push(redirect),
+                None => {
+                    self.pos = before;
+                    return Ok(redirects);
+                }
+            }
+        }
+    }
+
+    fn parse_if(&mut self) -> ParseResult<CompoundCommand> {
+        self.pos += "if".len();
+        let mut branches = Vec::new();
+        let mut else_body = None;
+        loop {
+            let condition = self.parse_list(&["then"])?;
+            self.expect_reserved("then")?;
+            let body = self.parse_list(&["elif", "else", "fi"])?;
+            branches.push((condition, body));
+            match self.peek_reserved() {
+                Some("elif") => self.pos += "elif".len(),
+                Some("else") => {
+                    self.pos += "else".len();
+                    else_body = Some(self.parse_list(&["fi"])?);
+                    self.expect_reserved("fi")?;
+                    break;
+                }
+                _ => {
+                    self.expect_reserved("fi")?;
+                    break;
+                }
+            }
+        }
+        Ok(CompoundCommand::If {
+            branches,
+            else_body,
+        })
+    }
+
+    fn parse_do_group(&mut self) -> ParseResult<Script> {
+        self.skip_linebreaks();
+        self.expect_reserved("do")?;
+        let body = self.parse_list(&["done"])?;
+        self.expect_reserved("done")?;
+        Ok(body)
+    }
+
+    /// Parses `for` and bash `select` loops, which share their syntax.
+    fn parse_for(&mut self, keyword: &str) -> ParseResult<CompoundCommand> {
+        self.pos += keyword.len();
+        let select = keyword == "select";
+        self.skip_blanks();
+        if !select && self.rest().starts_with("((") {
+            self.pos += 2;
+            let header = self.read_arithmetic()?;
+            self.skip_blanks();
+            if self.peek() == Some(';') {
+                self.pos += 1;
+            }
+            let body = self.parse_do_group()?;
+            return Ok(CompoundCommand::ArithmeticFor { header, body });
+        }
+        let variable = self
+            .read_name()
+            .ok_or_else(|| self.error("expected a loop variable"))?;
+        self.skip_blanks();
+        if self.peek() == Some(';') {
+            self.pos += 1;
+        }
+        self.skip_linebreaks();
+        let mut items = None;
+        if self.peek_reserved() == Some("in") {
+            self.pos += "in".len();
+            let mut words = Vec::new();
+            loop {
+                self.skip_blanks();
+                match self.peek() {
+                    Some(';') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some('\n') => {
+                        self.consume_newline();
+                        break;
+                    }
+                    None => break,
+                    Some(_) => match self.parse_word()? {
+                        Some(word) => words.push(word),
+                        None => return Err(self.unexpected()),
+                    },
+                }
+            }
+            items = Some(words);
+        }
+        let body = self.parse_do_group()?;
+        Ok(CompoundCommand::For {
+            variable,
+            items,
+            body,
+            select,
+        })
+    }
+
+    fn parse_case(&mut self) -> ParseResult<CompoundCommand> {
+        self.pos += "case".len();
+        self.skip_blanks();
+        let subject = self
+            .parse_word()?
+            .ok_or_else(|| self.error("expected a case subject"))?;
+        self.skip_linebreaks();
+        self.expect_reserved("in")?;
+        let mut arms = Vec::new();
+        loop {
+            self.skip_linebreaks();
+            if self.peek_reserved() == Some("esac") {
+                self.pos += "esac".len();
+                break;
+            }
+            if self.peek() == Some('(') {
+                self.pos += 1;
+            }
+            let mut patterns = Vec::new();
+            loop {
+                self.skip_blanks();
+                match self.parse_word()? {
+                    Some(word) => patterns.push(word),
+                    None => return Err(self.error("expected a case pattern")),
+                }
+                self.skip_blanks();
+                if self.peek() == Some('|') {
+                    self.pos += 1;
+                    continue;
+                }
+                self.expect_char(')')?;
+                break;
+            }
+            let body = self.parse_list(&["esac"])?;
+            arms.push(CaseArm { patterns, body });
+            self.skip_linebreaks();
+            for terminator in [";;&", ";;", ";&"] {
+                if self.rest().starts_with(terminator) {
+                    self.pos += terminator.len();
+                    break;
+                }
+            }
+        }
+        Ok(CompoundCommand::Case { subject, arms })
+    }
+
+    fn parse_conditional(&mut self) -> ParseResult<CompoundCommand> {
+        self.pos += "[[".len();
+        let mut words = Vec::new();
+        loop {
+            self.skip_linebreaks();
+            if self.peek_reserved() == Some("]]") {
+                self.pos += "]]".len();
+                return Ok(CompoundCommand::Conditional(words));
+            }
+            let start = self.pos;
+            let operator = ["&&", "||", "(", ")", "<", ">"]
+                .into_iter()
+                .find(|op| self.rest().starts_with(op));
+            if let Some(operator) = operator {
+                self.pos += operator.len();
+                words.push(Word {
+                    parts: vec![WordPart::Literal(operator.to_string())],
+                    span: self.span_from(start),
+                });
+                continue;
+            }
+            match self.parse_word()? {
+                Some(word) => words.push(word),
+                None => return Err(self.error("expected `]]`")),
+            }
+        }
+    }
+
+    fn parse_function_keyword(&mut self, start: usize) -> ParseResult<Command> {
+        self.pos += "function".len();
+        self.skip_blanks();
+        let name_start = self.pos;
+        let name = match self.parse_word()?.and_then(|word| word.static_value()) {
+            Some(name) => name,
+            None => {
+                self.pos = name_start;
+                return Err(self.error("expected a function name"));
+            }
+        };
+        self.skip_blanks();
+        if self.peek() == Some('(') {
+            return self.parse_function_body(start, name);
+        }
+        self.skip_linebreaks();
+        let body = self.parse_command()?;
+        Ok(Command::FunctionDef {
+            name,
+            body: Box::new(body),
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses `( ) body` after a function name.
+    fn parse_function_body(&mut self, start: usize, name: String) -> ParseResult<Command> {
+        self.expect_char('(')?;
+        self.skip_blanks();
+        self.expect_char(')')?;
+        self.skip_linebreaks();
+        let body = self.parse_command()?;
+        if matches!(body, Command::Simple(_)) {
+            return Err(self.error("function body must be a compound command"));
+        }
+        Ok(Command::FunctionDef {
+            name,
+            body: Box::new(body),
+            span: self.span_from(start),
+        })
+    }
+
+    // ---- assignments and redirections ----
+
+    fn try_parse_assignment(&mut self) -> ParseResult<Option<Assignment>> {
+        let start = self.pos;
+        let Some(name) = self.read_name() else {
+            return Ok(None);
+        };
+        // `name[subscript]=value` sets one array element.
+        if self.peek() == Some('[') && !self.skip_subscript() {
+            self.pos = start;
+            return Ok(None);
+        }
+        if self.rest().starts_with("+=") {
+            self.pos += 2;
+        } else if self.peek() == Some('=') {
+            self.pos += 1;
+        } else {
+            self.pos = start;
+            return Ok(None);
+        }
+        let value = if self.peek() == Some('(') {
+            self.skip_balanced_parens()?;
+            None
+        } else {
+            let value_start = self.pos;
+            Some(self.parse_word()?.unwrap_or(Word {
+                parts: Vec::new(),
+                span: self.span_from(value_start),
+            }))
+        };
+        Ok(Some(Assignment {
+            name,
+            value,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// `name=(...)` as an argument of `declare`, `local` and the like, kept as one literal
+    /// word. As in array assignments, the elements are not parsed.
+    fn try_parse_array_argument(&mut self) -> ParseResult<Option<Word>> {
+        let start = self.pos;
+        if let Some(Assignment { value: None, .. }) = self.try_parse_assignment()? {
+            return Ok(Some(Word {
+                parts: vec![WordPart::Literal(self.src[start..self.pos].to_string())],
+                span: self.span_from(start),
+            }));
+        }
+        self.pos = start;
+        Ok(None)
+    }
+
+    /// Moves past a `[...]` subscript. Fails at a blank or operator before the closing
+    /// bracket, where the shell no longer reads an assignment.
+    fn skip_subscript(&mut self) -> bool {
+        let mut depth = 0usize;
+        for (offset, ch) in self.rest().char_indices() {
+            match ch {
+                '[' => depth += 1,
+                ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.pos += offset + 1;
+                        return true;
+                    }
+                }
+                ch if ch.is_whitespace() || matches!(ch, ';' | '&' | '|' | '<' | '>') => {
+                    return false;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    fn try_parse_redirect(&mut self) -> ParseResult<Option<Redirect>> {
+        let start = self.pos;
+        let digits = self.rest().chars().take_while(char::is_ascii_digit).count();
+        let fd = if digits > 0 {
+            self.rest()[..digits].parse().ok()
+        } else {
+            None
+        };
+        self.pos += digits;
+        if fd.is_none() && self.at_process_substitution() {
+            self.pos = start;
+            return Ok(None);
+        }
+        let Some((text, op)) = REDIRECT_OPS
+            .iter()
+            .copied()
+            .find(|(text, _)| self.rest().starts_with(text))
+        else {
+            self.pos = start;
+            return Ok(None);
+        };
+        if digits > 0 && text.starts_with('&') {
+            self.pos = start;
+            return Ok(None);
+        }
+        self.pos += text.len();
+        self.skip_blanks();
+        let target_start = self.pos;
+        let Some(word) = self.parse_word()? else {
+            return Err(self.error(format!("expected a target after `{}`", text)));
+        };
+        let target = if op == RedirectOp::HereDoc {
+            let raw = &self.src[target_start..self.pos];
+            RedirectTarget::HereDoc(self.read_heredoc(&word, raw, text == "<<-")?)
+        } else {
+            RedirectTarget::Word(word)
+        };
+        Ok(Some(Redirect {
+            fd,
+            op,
+            target,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Reads the body of a here-document whose `<<` operator is on the current line. The
+    /// body starts after that line (or after an earlier body for the same line), and the
+    /// parser jumps past it when it reaches the line's newline.
+    fn read_heredoc(
+        &mut self,
+        delimiter: &Word,
+        raw: &str,
+        strip_tabs: bool,
+    ) -> ParseResult<HereDoc> {
+        let quoted = raw.contains(['\'', '"', '\\']);
+        let delimiter = delimiter
+            .static_value()
+            .ok_or_else(|| self.error("here-document delimiter must not contain expansions"))?;
+        let (newline, body_start) = match self.heredoc_skip {
+            Some(pending) => pending,
+            // Without a following line the body is empty, as bash warns but accepts.
+            None => match self.find_line_end(self.pos) {
+                Some(newline) => (newline, newline + 1),
+                None => (self.src.len(), self.src.len()),
+            },
+        };
+        let mut line_start = body_start;
+        loop {
+            // Like bash, an unterminated here-document runs to the end of input.
+            line_start = line_start.min(self.src.len());
+            let at_end = line_start == self.src.len();
+            let line_end = self.src[line_start..]
+                .find('\n')
+                .map(|offset| line_start + offset)
+                .unwrap_or(self.src.len());
+            let line = &self.src[line_start..line_end];
+            let line = if strip_tabs {
+                line.trim_start_matches('\t')
+            } else {
+                line
+            };
+            if at_end || line == delimiter {
+                let resume = (line_end + 1).min(self.src.len());
+                self.heredoc_skip = Some((newline, resume));
+                let parts = if quoted {
+                    Vec::new()
+                } else {
+                    Parser::with_base(&self.src[body_start..line_start], self.base + body_start)
+                        .parse_expansion_text(false)?
+                };
+                let body = if strip_tabs {
+                    self.src[body_start..line_start]
+                        .lines()
+                        .map(|line| format!("{}\n", line.trim_start_matches('\t')))
+                        .collect()
+                } else {
+                    self.src[body_start..line_start].to_string()
+                };
+                return Ok(HereDoc {
+                    delimiter,
+                    quoted,
+                    strip_tabs,
+                    body,
+                    parts,
+                    body_span: Span::new(self.base + body_start, self.base + line_start),
+                });
+            }
+            line_start = line_end + 1;
+        }
+    }
+
+    /// Position of the newline ending the line that contains `from`, skipping quoted text.
+    fn find_line_end(&self, from: usize) -> Option<usize> {
+        let mut quote = None;
+        let mut escaped = false;
+        for (offset, ch) in self.src[from..].char_indices() {
+            if escaped {
+                escaped = false;
+                continue;
+            }
+            match (quote, ch) {
+                (Some('\''), '\'') => quote = None,
+                (Some('\''), _) => {}
+                (_, '\\') => escaped = true,
+                (Some('"'), '"') => quote = None,
+                (None, '\'' | '"') => quote = Some(ch),
+                (None, '\n') => return Some(from + offset),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // ---- words ----
+
+    fn at_process_substitution(&self) -> bool {
+        self.rest().starts_with("<(") || self.rest().starts_with(">(")
+    }
+
+    /// Parses one word at the current position, or returns `None` if an operator or the
+    /// end of input comes first.
+    fn parse_word(&mut self) -> ParseResult<Option<Word>> {
+        let start = self.pos;
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        while let Some(ch) = self.peek() {
+            if self.pos == start && self.at_process_substitution() {
+                let input = ch == '<';
+                self.pos += 2;
+                let script = self.parse_list(&[])?;
+                self.expect_char(')')?;
+                parts.push(WordPart::ProcessSubstitution { input, script });
+                continue;
+            }
+            match ch {
+                ' ' | '\t' | '\n' | '|' | '&' | ';' | '(' | ')' | '<' | '>' => break,
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('\n') => self.pos += 1,
+                        Some(escaped) => {
+                            literal.push(escaped);
+                            self.pos += escaped.len_utf8();
+                        }
+                        None => literal.push('\\'),
+                    }
+                }
+                '\'' => {
+                    flush_literal(&mut literal, &mut parts);
+                    self.pos += 1;
+                    parts.push(WordPart::SingleQuoted(self.read_single_quoted(false)?));
+                }
+                '"' => {
+                    flush_literal(&mut literal, &mut parts);
+                    self.pos += 1;
+                    parts.push(WordPart::DoubleQuoted(self.parse_double_quoted()?));
+                }
+                '$' | '`' => {
+                    flush_literal(&mut literal, &mut parts);
+                    let part = if ch == '$' {
+                        self.parse_dollar()?
+                    } else {
+                        self.parse_backtick()?
+                    };
+                    parts.push(part);
+                }
+                _ => {
+                    literal.push(ch);
+                    self.pos += ch.len_utf8();
+                }
+            }
+        }
+        flush_literal(&mut literal, &mut parts);
+        if self.pos == start {
+            return Ok(None);
+        }
+        Ok(Some(Word {
+            parts,
+            span: self.span_from(start),
+        }))
+    }
+
+    /// Reads up to the closing `'`; with `ansi`, backslash escapes (as in `$'...'`) may
+    /// hide a quote.
+    fn read_single_quoted(&mut self, ansi: bool) -> ParseResult<String> {
+        let start = self.pos;
+        let mut escaped = false;
+        for (offset, ch) in self.rest().char_indices() {
+            if escaped {
+                escaped = false;
+            } else if ansi && ch == '\\' {
+                escaped = true;
+            } else if ch == '\'' {
+                let text = self.src[start..start + offset].to_string();
+                self.pos = start + offset + 1;
+                return Ok(text);
+            }
+        }
+        Err(self.error_at(start - 1, "unterminated single quote"))
+    }
+
+    fn parse_double_quoted(&mut self) -> ParseResult<Vec<WordPart>> {
+        let start = self.pos - 1;
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        loop {
+            let Some(ch) = self.peek() else {
+                return Err(self.error_at(start, "unterminated double quote"));
+            };
+            match ch {
+                '"' => {
+                    self.pos += 1;
+                    break;
+                }
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('\n') => self.pos += 1,
+                        Some(escaped @ ('$' | '`' | '"' | '\\')) => {
+                            literal.push(escaped);
+                            self.pos += 1;
+                        }
+                        _ => literal.push('\\'),
+                    }
+                }
+                '$' | '`' => {
+                    flush_literal(&mut literal, &mut parts);
+                    let part = if ch == '$' {
+                        self.parse_dollar()?
+                    } else {
+                        self.parse_backtick()?
+                    };
+                    parts.push(part);
+                }
+                _ => {
+                    literal.push(ch);
+                    self.pos += ch.len_utf8();
+                }
+            }
+        }
+        flush_literal(&mut literal, &mut parts);
+        Ok(parts)
+    }
+
+    fn parse_dollar(&mut self) -> ParseResult<WordPart> {
+        let start = self.pos;
+        self.pos += 1;
+        let rest = self.rest();
+        if rest.starts_with("((")
+            && let Some(expression) = self.try_read_arithmetic()
+        {
+            return Ok(WordPart::Arithmetic(expression));
+        }
+        if rest.starts_with('(') {
+            self.pos += 1;
+            let script = self.parse_list(&[])?;
+            self.expect_char(')')?;
+            return Ok(WordPart::CommandSubstitution(script));
+        }
+        if rest.starts_with('{') {
+            let end = self
+                .find_closing_brace(self.pos)
+                .ok_or_else(|| self.error_at(start, "unterminated `${`"))?;
+            let text = self.src[self.pos..=end].to_string();
+            let parts = Parser::with_base(&self.src[self.pos + 1..end], self.base + self.pos + 1)
+                .parse_expansion_text(true)?;
+            self.pos = end + 1;
+            return Ok(WordPart::BracedParameter { text, parts });
+        }
+        if rest.starts_with('\'') {
+            self.pos += 1;
+            return Ok(WordPart::SingleQuoted(self.read_single_quoted(true)?));
+        }
+        if rest.starts_with('"') {
+            self.pos += 1;
+            return Ok(WordPart::DoubleQuoted(self.parse_double_quoted()?));
+        }
+        if let Some(name) = self.read_name() {
+            return Ok(WordPart::Parameter(name));
+        }
+        match self.peek() {
+            Some(ch @ ('0'..='9' | '@' | '*' | '#' | '?' | '-' | '$' | '!')) => {
+                self.pos += 1;
+                Ok(WordPart::Parameter(ch.to_string()))
+            }
+            _ => Ok(WordPart::Literal("$".to_string())),
+        }
+    }
+
+    /// Parses the whole source as text that undergoes expansion: the inside of `${...}`
+    /// (with `quotes`, as in `${x:-"a b"}`) or an unquoted here-document body, where
+    /// quotes are literal.
+    fn parse_expansion_text(mut self, quotes: bool) -> ParseResult<Vec<WordPart>> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        while let Some(ch) = self.peek() {
+            match ch {
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('\n') => self.pos += 1,
+                        Some(escaped) => {
+                            literal.push(escaped);
+                            self.pos += escaped.len_utf8();
+                        }
+                        None => literal.push('\\'),
+                    }
+                }
+                '\'' if quotes => {
+                    flush_literal(&mut literal, &mut parts);
+                    self.pos += 1;
+                    parts.push(WordPart::SingleQuoted(self.read_single_quoted(false)?));
+                }
+                '"' if quotes => {
+                    flush_literal(&mut literal, &mut parts);
+                    self.pos += 1;
+                    parts.push(WordPart::DoubleQuoted(self.parse_double_quoted()?));
+                }
+                '$' | '`' => {
+                    flush_literal(&mut literal, &mut parts);
+                    let part = if ch == '$' {
+                        self.parse_dollar()?
+                    } else {
+                        self.parse_backtick()?
+                    };
+                    parts.push(part);
+                }
+                _ => {
+                    literal.push(ch);
+                    self.pos += ch.len_utf8();
+                }
+            }
+        }
+        flush_literal(&mut literal, &mut parts);
+        Ok(parts)
+    }
+
+    /// Parses `` `...` ``. The body is unescaped and parsed on its own, so spans inside it
+    /// are only exact when it contains no backslash escapes.
+    fn parse_backtick(&mut self) -> ParseResult<WordPart> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut body = String::new();
+        loop {
+            let Some(ch) = self.peek() else {
+                return Err(self.error_at(start, "unterminated backtick"));
+            };
+            self.pos += ch.len_utf8();
+            match ch {
+                '`' => break,
+                '\\' => match self.peek() {
+                    Some(escaped @ ('`' | '\\' | '$')) => {
+                        body.push(escaped);
+                        self.pos += 1;
+                    }
+                    _ => body.push('\\'),
+                },
+                _ => body.push(ch),
+            }
+        }
+        let script = Parser::with_base(&body, self.base + start + 1).parse_script()?;
+        Ok(WordPart::Backtick(script))
+    }
+
+    /// Reads `((...))` at the current position. Like bash, input that does not close with
+    /// `))`, as in `((cd a) && ls)`, is left for the caller to parse as nested subshells.
+    fn try_read_arithmetic(&mut self) -> Option<String> {
+        let start = self.pos;
+        self.pos += 2;
+        let expression = self.read_arithmetic().ok();
+        if expression.is_none() {
+            self.pos = start;
+        }
+        expression
+    }
+
+    /// Reads the text of `((...))` after the opening parentheses, up to the matching `))`.
+    fn read_arithmetic(&mut self) -> ParseResult<String> {
+        let start = self.pos;
+        let mut depth = 0usize;
+        for (offset, ch) in self.rest().char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' if depth > 0 => depth -= 1,
+                ')' if self.src[start + offset..].starts_with("))") => {
+                    self.pos = start + offset + 2;
+                    return Ok(self.src[start..start + offset].to_string());
+                }
+                _ => {}
+            }
+        }
+        Err(self.error_at(start, "unterminated `((`"))
+    }
+
+    /// Index of the `}` closing the `{` at `open`, skipping quoted text and nested braces.
+    fn find_closing_brace(&self, open: usize) -> Option<usize> {
+        let mut depth = 0usize;
+        let mut quote = None;
+        let mut escaped = false;
+        for (offset, ch) in self.src[open..].char_indices() {
+            if escaped {
+                escaped = false;
+                continue;
+            }
+            match (quote, ch) {
+                (Some('\''), '\'') => quote = None,
+                (Some('\''), _) => {}
+                (_, '\\') => escaped = true,
+                (Some('"'), '"') => quote = None,
+                (Some(_), _) => {}
+                (None, '\'' | '"') => quote = Some(ch),
+                (None, '{') => depth += 1,
+                (None, '}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(open + offset);
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn skip_balanced_parens(&mut self) -> ParseResult<()> {
+        let start = self.pos;
+        let mut depth = 0usize;
+        let mut quote = None;
+        let mut escaped = false;
+        for (offset, ch) in self.rest().char_indices() {
+            if escaped {
+                escaped = false;
+                continue;
+            }
+            match (quote, ch) {
+                (Some('\''), '\'') => quote = None,
+                (Some('\''), _) => {}
+                (_, '\\') => escaped = true,
+                (Some('"'), '"') => quote = None,
+                (Some(_), _) => {}
+                (None, '\'' | '"') => quote = Some(ch),
+                (None, '(') => depth += 1,
+                (None, ')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.pos = start + offset + 1;
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(self.error_at(start, "unterminated `(`"))
+    }
+
+    // ---- low-level helpers ----
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn read_name(&mut self) -> Option<String> {
+        let rest = self.rest();
+        let first = rest.chars().next()?;
+        if !(first == '_' || first.is_ascii_alphabetic()) {
+            return None;
+        }
+        let len = rest
+            .find(|ch: char| !(ch == '_' || ch.is_ascii_alphanumeric()))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        Some(rest[..len].to_string())
+    }
+
+    /// The unquoted word at the current position if it could be a reserved word.
+    fn peek_reserved(&self) -> Option<&'a str> {
+        let rest = self.rest();
+        let len = rest
+            .find(|ch: char| {
+                ch.is_whitespace() || matches!(ch, '|' | '&' | ';' | '(' | ')' | '<' | '>')
+            })
+            .unwrap_or(rest.len());
+        let word = &rest[..len];
+        let reserved = matches!(
+            word,
+            "if" | "then"
+                | "elif"
+                | "else"
+                | "fi"
+                | "for"
+                | "select"
+                | "in"
+                | "do"
+                | "done"
+                | "while"
+                | "until"
+                | "case"
+                | "esac"
+                | "function"
+                | "time"
+                | "{"
+                | "}"
+                | "!"
+                | "[["
+                | "]]"
+        );
+        reserved.then_some(word)
+    }
+
+    fn expect_reserved(&mut self, word: &str) -> ParseResult<()> {
+        self.skip_blanks();
+        if self.peek_reserved() == Some(word) {
+            self.pos += word.len();
+            return Ok(());
+        }
+        Err(self.error(format!("expected `{}`", word)))
+    }
+
+    fn expect_char(&mut self, expected: char) -> ParseResult<()> {
+        self.skip_blanks();
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            return Ok(());
+        }
+        Err(self.error(format!("expected `{}`", expected)))
+    }
+
+    /// Skips spaces, tabs, line continuations and comments, but not newlines.
+    fn skip_blanks(&mut self) {
+        loop {
+            let rest = self.rest();
+            if rest.starts_with([' ', '\t']) {
+                self.pos += 1;
+            } else if rest.starts_with("\\\n") {
+                self.pos += 2;
+            } else if rest.starts_with('#') {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn skip_linebreaks(&mut self) {
+        loop {
+            self.skip_blanks();
+            if self.peek() == Some('\n') {
+                self.consume_newline();
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn consume_newline(&mut self) {
+        match self.heredoc_skip.take() {
+            Some((newline, resume)) if newline == self.pos => self.pos = resume,
+            pending => {
+                self.heredoc_skip = pending;
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn span_from(&self, start: usize) -> Span {
+        Span::new(self.base + start, self.base + self.pos)
+    }
+
+    fn unexpected(&self) -> ParseError {
+        match self.peek() {
+            Some('\n') => self.error("unexpected newline"),
+            Some(_) => {
+                let token = self
+                    .rest()
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .chars()
+                    .take(16)
+                    .collect::<String>();
+                self.error(format!("unexpected `{}`", token))
+            }
+            None => self.error("unexpected end of input"),
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            message: message.into(),
+            offset: self.base + pos,
+        }
+    }
+}
+
+fn flush_literal(literal: &mut String, parts: &mut Vec<WordPart>) {
+    if !literal.is_empty() {
+        parts.push(WordPart::Literal(std::mem::take(literal)));
+    }
+}
+
+/// Whether the words so far start a builtin that takes `name=(...)` arguments.
+fn is_declaration(words: &[Word]) -> bool {
+    words
+        .first()
+        .and_then(Word::static_value)
+        .is_some_and(|name| {
+            matches!(
+                name.as_str(),
+                "declare" | "local" | "typeset" | "export" | "readonly"
+            )
+        })
+}
@@ -0,0 +1,185 @@
+use super::ast::{
+    Command, CompoundCommand, Pipeline, Redirect, RedirectTarget, Script, SimpleCommand, Word,
+    WordPart,
+};
+
+/// Calls `visit` for every simple command in `script` in source order, descending into
+/// subshells, groups, compound commands and function bodies. Commands inside word
+/// expansions such as `$(...)` are not visited.
+pub fn for_each_simple_command<'a>(script: &'a Script, visit: &mut impl FnMut(&'a SimpleCommand)) {
+    walk_script(script, false, &mut Commands(visit));
+}
+
+/// Like `for_each_simple_command`, but also visits the commands of `$(...)`, backtick and
+/// `<(...)` substitutions wherever they appear, including inside `${...}` and unquoted
+/// here-document bodies, each right after the command containing them.
+pub fn for_each_simple_command_deep<'a>(
+    script: &'a Script,
+    visit: &mut impl FnMut(&'a SimpleCommand),
+) {
+    walk_script(script, true, &mut Commands(visit));
+}
+
+/// Calls `visit` for every redirection in `script`, on simple and compound commands alike
+/// and inside substitutions.
+pub fn for_each_redirect<'a>(script: &'a Script, visit: &mut impl FnMut(&'a Redirect)) {
+    walk_script(script, true, &mut Redirects(visit));
+}
+
+/// Calls `visit` for every pipeline in `script`, including those inside compound commands
+/// and substitutions.
+pub fn for_each_pipeline<'a>(script: &'a Script, visit: &mut impl FnMut(&'a Pipeline)) {
+    walk_script(script, true, &mut Pipelines(visit));
+}
+
+/// Every simple command in `script`, in source order (see `for_each_simple_command`).
+pub fn simple_commands(script: &Script) -> Vec<&SimpleCommand> {
+    let mut commands = Vec::new();
+    for_each_simple_command(script, &mut |command| commands.push(command));
+    commands
+}
+
+trait Visitor<'a> {
+    fn pipeline(&mut self, _pipeline: &'a Pipeline) {}
+    fn command(&mut self, _command: &'a SimpleCommand) {}
+    fn redirect(&mut self, _redirect: &'a Redirect) {}
+}
+
+struct Pipelines<'v, F>(&'v mut F);
+
+impl<'a, F: FnMut(&'a Pipeline)> Visitor<'a> for Pipelines<'_, F> {
+    fn pipeline(&mut self, pipeline: &'a Pipeline) {
+        (self.0)(pipeline)
+    }
+}
+
+struct Commands<'v, F>(&'v mut F);
+
+impl<'a, F: FnMut(&'a SimpleCommand)> Visitor<'a> for Commands<'_, F> {
+    fn command(&mut self, command: &'a SimpleCommand) {
+        (self.0)(command)
+    }
+}
+
+struct Redirects<'v, F>(&'v mut F);
+
+impl<'a, F: FnMut(&'a Redirect)> Visitor<'a> for Redirects<'_, F> {
+    fn redirect(&mut self, redirect: &'a Redirect) {
+        (self.0)(redirect)
+    }
+}
+
+fn walk_script<'a>(script: &'a Script, deep: bool, visit: &mut impl Visitor<'a>) {
+    for item in &script.items {
+        let and_or = &item.and_or;
+        for pipeline in std::iter::once(&and_or.first).chain(and_or.rest.iter().map(|(_, p)| p)) {
+            visit.pipeline(pipeline);
+            for command in &pipeline.commands {
+                walk_command(command, deep, visit);
+            }
+        }
+    }
+}
+
+fn walk_command<'a>(command: &'a Command, deep: bool, visit: &mut impl Visitor<'a>) {
+    match command {
+        Command::Simple(simple) => {
+            visit.command(simple);
+            if deep {
+                let values = simple.assignments.iter().filter_map(|a| a.value.as_ref());
+                walk_words(values.chain(&simple.words), visit);
+                walk_redirects(&simple.redirects, visit);
+            }
+        }
+        Command::FunctionDef { body, .. } => walk_command(body, deep, visit),
+        Command::Compound {
+            body, redirects, ..
+        } => {
+            match body {
+                CompoundCommand::Subshell(script) | CompoundCommand::Group(script) => {
+                    walk_script(script, deep, visit)
+                }
+                CompoundCommand::If {
+                    branches,
+                    else_body,
+                } => {
+                    for (condition, body) in branches {
+                        walk_script(condition, deep, visit);
+                        walk_script(body, deep, visit);
+                    }
+                    if let Some(body) = else_body {
+                        walk_script(body, deep, visit);
+                    }
+                }
+                CompoundCommand::While {
+                    condition, body, ..
+                } => {
+                    walk_script(condition, deep, visit);
+                    walk_script(body, deep, visit);
+                }
+                CompoundCommand::For { items, body, .. } => {
+                    if deep && let Some(items) = items {
+                        walk_words(items, visit);
+                    }
+                    walk_script(body, deep, visit)
+                }
+                CompoundCommand::ArithmeticFor { body, .. } => walk_script(body, deep, visit),
+                CompoundCommand::Case { subject, arms } => {
+                    if deep {
+                        walk_words(std::iter::once(subject), visit);
+                    }
+                    for arm in arms {
+                        if deep {
+                            walk_words(&arm.patterns, visit);
+                        }
+                        walk_script(&arm.body, deep, visit);
+                    }
+                }
+                CompoundCommand::Conditional(words) => {
+                    if deep {
+                        walk_words(words, visit);
+                    }
+                }
+                CompoundCommand::Arithmetic(_) => {}
+            }
+            if deep {
+                walk_redirects(redirects, visit);
+            }
+        }
+    }
+}
+
+fn walk_redirects<'a>(redirects: &'a [Redirect], visit: &mut impl Visitor<'a>) {
+    for redirect in redirects {
+        visit.redirect(redirect);
+    }
+    for redirect in redirects {
+        match &redirect.target {
+            RedirectTarget::Word(word) => walk_parts(&word.parts, visit),
+            RedirectTarget::HereDoc(heredoc) => walk_parts(&heredoc.parts, visit),
+        }
+    }
+}
+
+fn walk_words<'a>(words: impl IntoIterator<Item = &'a Word>, visit: &mut impl Visitor<'a>) {
+    for word in words {
+        walk_parts(&word.parts, visit);
+    }
+}
+
+fn walk_parts<'a>(parts: &'a [WordPart], visit: &mut impl Visitor<'a>) {
+    for part in parts {
+        match part {
+            WordPart::DoubleQuoted(inner) | WordPart::BracedParameter { parts: inner, .. } => {
+                walk_parts(inner, visit)
+            }
+            WordPart::CommandSubstitution(script)
+            | WordPart::Backtick(script)
+            | WordPart::ProcessSubstitution { script, .. } => walk_script(script, true, visit),
+            WordPart::Literal(_)
+            | WordPart::SingleQuoted(_)
+            | WordPart::Parameter(_)
+            | WordPart::Arithmetic(_) => {}
+        }
+    }
+}
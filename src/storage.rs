@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Advisory exclusive lock held until dropped. Other `cg` processes writing the same
+/// data block on it; readers never need it because writes are atomic renames.
+pub struct FileLock {
+    _file: File,
+}
+
+/// Locks `<path>.lock`, creating it (and its directory) if needed.
+pub fn lock_exclusive(path: &Path) -> Result<FileLock> {
+    let lock_path = sibling_path(path, "lock");
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .with_context(|| format!("failed to open lock file: {}", lock_path.display()))?;
+    file.lock()
+        .with_context(|| format!("failed to lock {}", lock_path.display()))?;
+    Ok(FileLock { _file: file })
+}
+
+/// Writes `contents` to a temporary file next to `path`, syncs it and renames it over
+/// `path`, so readers see either the old or the new content but never a partial write.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp = sibling_path(path, &format!("{}.tmp", std::process::id()));
+    let result = (|| -> Result<()> {
+        let mut file = File::create(&temp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result.with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_write_replaces_content_and_cleans_up() {
+        let dir = std::env::temp_dir().join(format!("cg-storage-test-{}", std::process::id()));
+        let path = dir.join("data.json");
+        write_atomic(&path, b"first").unwrap();
+        {
+            let _lock = lock_exclusive(&path).unwrap();
+            write_atomic(&path, b"second").unwrap();
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        let leftovers = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+use super::{PROBE_TIMEOUT, modified_time, probe};
+use crate::meta;
+
+/// A tool the prompt reports, probed through `binary` with the first `args` that print
+/// a version.
+struct InventoryProbe {
+    name: &'static str,
+    binary: &'static str,
+    args: &'static [&'static [&'static str]],
+}
+
+/// Tools whose flavor changes which options and syntax a command may use.
+const INVENTORY: &[InventoryProbe] = &[
+    InventoryProbe {
+        name: "coreutils",
+        binary: "ls",
+        args: &[&["--version"]],
+    },
+    InventoryProbe {
+        name: "sed",
+        binary: "sed",
+        args: &[&["--version"]],
+    },
+    InventoryProbe {
+        name: "grep",
+        binary: "grep",
+        args: &[&["--version"]],
+    },
+    InventoryProbe {
+        name: "find",
+        binary: "find",
+        args: &[&["--version"]],
+    },
+    InventoryProbe {
+        name: "awk",
+        binary: "awk",
+        args: &[&["--version"], &["-W", "version"]],
+    },
+    InventoryProbe {
+        name: "busybox",
+        binary: "busybox",
+        args: &[&["--help"]],
+    },
+    InventoryProbe {
+        name: "git",
+        binary: "git",
+        args: &[&["--version"]],
+    },
+    InventoryProbe {
+        name: "jq",
+        binary: "jq",
+        args: &[&["--version"]],
+    },
+];
+
+/// What is installed for one inventory entry, cached in the meta cache until the binary
+/// path or its modification time changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolInfo {
+    pub name: String,
+    /// `None` when the tool is not installed.
+    pub path: Option<String>,
+    pub modified: Option<i64>,
+    /// `GNU`, `BSD`, `busybox`, `mawk`, ... when it can be told.
+    pub flavor: Option<String>,
+    pub version: Option<String>,
+}
+
+impl fmt::Display for ToolInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_none() {
+            return write!(f, "{} not installed", self.name);
+        }
+        write!(f, "{}", self.name)?;
+        if self.flavor.is_none() && self.version.is_none() {
+            return write!(f, " (unknown version)");
+        }
+        for detail in [&self.flavor, &self.version].into_iter().flatten() {
+            write!(f, " {detail}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The installed flavors and versions of the inventory tools, e.g. `sed GNU 4.9`. Only
+/// tools that changed since the last run are probed again.
+pub fn tool_inventory() -> Vec<ToolInfo> {
+    let cached = meta::cached_tools().unwrap_or_default();
+    let inventory = INVENTORY
+        .iter()
+        .map(|entry| {
+            let path = which::which(entry.binary).ok();
+            let modified = path.as_deref().and_then(modified_time);
+            let path_text = path.as_ref().map(|path| path.display().to_string());
+            if let Some(tool) = cached.iter().find(|tool| {
+                tool.name == entry.name && tool.path == path_text && tool.modified == modified
+            }) {
+                return tool.clone();
+            }
+            let (flavor, version) = path
+                .as_deref()
+                .map(|path| identify(&probe_version(path, entry.args)))
+                .unwrap_or_default();
+            ToolInfo {
+                name: entry.name.to_string(),
+                path: path_text,
+                modified,
+                flavor,
+                version,
+            }
+        })
+        .collect::<Vec<_>>();
+    if inventory != cached {
+        // The inventory is a hint for the model; failing to cache it only costs a probe.
+        let _ = meta::set_cached_tools(inventory.clone());
+    }
+    inventory
+}
+
+fn probe_version(path: &Path, args: &[&[&str]]) -> String {
+    let mut text = String::new();
+    for args in args {
+        let Some(output) = probe(
+            Command::new(path).args(*args).env("LC_ALL", "C"),
+            PROBE_TIMEOUT,
+        ) else {
+            continue;
+        };
+        text = String::from_utf8_lossy(&output.stdout).into_owned();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        if output.status.success() {
+            break;
+        }
+    }
+    text
+}
+
+/// Reads the flavor and version from `--version` output. BSD tools reject
+/// `--version` and print their usage instead.
+fn identify(text: &str) -> (Option<String>, Option<String>) {
+    let lowered = text.to_lowercase();
+    let flavor = if text.contains("BusyBox") {
+        Some("busybox")
+    } else if lowered.contains("toybox") {
+        Some("toybox")
+    } else if lowered.contains("uutils") {
+        Some("uutils")
+    } else if text.contains("BSD") || lowered.starts_with("awk version") {
+        Some("BSD")
+    } else if text.contains("GNU") {
+        Some("GNU")
+    } else if lowered.contains("mawk") {
+        Some("mawk")
+    } else if lowered.contains("gojq") {
+        Some("gojq")
+    } else if lowered.contains("usage") || lowered.contains("illegal option") {
+        Some("BSD")
+    } else {
+        None
+    };
+    let line = match flavor {
+        Some("busybox") => text.lines().find(|line| line.contains("BusyBox")),
+        _ => text.lines().find(|line| !line.trim().is_empty()),
+    };
+    let version = line.and_then(|line| {
+        line.split(|c: char| c.is_whitespace() || matches!(c, ',' | '(' | ')' | '-'))
+            .map(|token| {
+                token
+                    .strip_prefix('v')
+                    .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+                    .unwrap_or(token)
+            })
+            .find(|token| {
+                token.starts_with(|c: char| c.is_ascii_digit())
+                    && (token.contains('.') || token.len() >= 8)
+            })
+            .map(str::to_string)
+    });
+    (flavor.map(str::to_string), version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(flavor: Option<&str>, version: Option<&str>) -> (Option<String>, Option<String>) {
+        (flavor.map(str::to_string), version.map(str::to_string))
+    }
+
+    #[test]
+    fn identifies_flavors_and_versions() {
+        assert_eq!(
+            identify("ls (GNU coreutils) 9.1\nCopyright (C) 2022"),
+            found(Some("GNU"), Some("9.1"))
+        );
+        assert_eq!(
+            identify("GNU Awk 5.2.1, API 3.2, PMA Avon 8-g1"),
+            found(Some("GNU"), Some("5.2.1"))
+        );
+        assert_eq!(
+            identify("grep (BSD grep, GNU compatible) 2.6.0-FreeBSD"),
+            found(Some("BSD"), Some("2.6.0"))
+        );
+        assert_eq!(
+            identify(
+                "ls: unrecognized option '--version'\nBusyBox v1.36.1 (2023-07-27) multi-call binary."
+            ),
+            found(Some("busybox"), Some("1.36.1"))
+        );
+        assert_eq!(
+            identify(
+                "sed: illegal option -- -\nusage: sed script [-Ealnru] [-i extension] [file ...]"
+            ),
+            found(Some("BSD"), None)
+        );
+        assert_eq!(
+            identify("mawk 1.3.4 20200120"),
+            found(Some("mawk"), Some("1.3.4"))
+        );
+        assert_eq!(identify("jq-1.6"), found(None, Some("1.6")));
+        assert_eq!(identify("git version 2.39.2"), found(None, Some("2.39.2")));
+    }
+
+    #[test]
+    fn renders_compact_entries() {
+        let tool = ToolInfo {
+            name: "sed".to_string(),
+            path: Some("/usr/bin/sed".to_string()),
+            modified: Some(1),
+            flavor: Some("GNU".to_string()),
+            version: Some("4.9".to_string()),
+        };
+        assert_eq!(tool.to_string(), "sed GNU 4.9");
+        let missing = ToolInfo {
+            name: "jq".to_string(),
+            path: None,
+            modified: None,
+            flavor: None,
+            version: None,
+        };
+        assert_eq!(missing.to_string(), "jq not installed");
+    }
+}
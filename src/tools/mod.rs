@@ -0,0 +1,88 @@
+mod inventory;
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+pub use inventory::{ToolInfo, tool_inventory};
+
+/// How long a tool may take to print its help or version before it is given up on.
+pub(crate) const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Runs `command` without stdin and collects its output. Returns `None` when it cannot
+/// be started or is still running after `timeout`, in which case it is killed.
+pub(crate) fn probe(command: &mut Command, timeout: Duration) -> Option<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+    // Drain both pipes concurrently so a chatty tool cannot block on a full pipe.
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+    let collect = |reader: Option<std::thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default()
+    };
+    Some(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+fn drain(mut pipe: impl Read + Send + 'static) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+/// Modification time of `path` (following symlinks) in seconds, used to notice that a
+/// cached tool has been upgraded or replaced.
+pub(crate) fn modified_time(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let seconds = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    i64::try_from(seconds).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probes_with_timeout() {
+        let output = probe(
+            Command::new("sh").args(["-c", "echo out; echo err >&2"]),
+            PROBE_TIMEOUT,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "out\n");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "err\n");
+        assert!(
+            probe(
+                Command::new("sh").args(["-c", "sleep 5"]),
+                Duration::from_millis(100)
+            )
+            .is_none()
+        );
+    }
+}
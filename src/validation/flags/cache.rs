@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::help::{KnownFlags, probe_flags};
+use crate::schema::Schema;
+use crate::{paths, storage, tools};
+
+/// The cache only saves probes, so it is rebuilt rather than migrated when unreadable;
+/// bumping the version still lets older builds tell a newer layout apart.
+const FLAG_CACHE_SCHEMA: Schema = Schema {
+    name: "flag cache",
+    version_field: "schemaVersion",
+    migrations: &[],
+};
+
+/// Options parsed from each tool's help, keyed by binary path. An entry is reused while
+/// the binary's modification time is unchanged.
+#[derive(Debug, Serialize, Deserialize)]
+struct FlagCache {
+    #[serde(rename = "schemaVersion")]
+    schema_version: u32,
+    #[serde(default)]
+    tools: BTreeMap<String, CachedFlags>,
+}
+
+impl Default for FlagCache {
+    fn default() -> Self {
+        Self {
+            schema_version: FLAG_CACHE_SCHEMA.current_version(),
+            tools: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedFlags {
+    modified: i64,
+    /// `None` when the help could not be parsed; cached too, so it is not retried.
+    flags: Option<KnownFlags>,
+}
+
+/// Known options of the binary at `path`, probing it on a cache miss.
+pub(super) fn known_flags(path: &Path) -> Option<KnownFlags> {
+    let modified = tools::modified_time(path)?;
+    let key = path.display().to_string();
+    if let Some(cached) = read_cache().tools.get(&key)
+        && cached.modified == modified
+    {
+        return cached.flags.clone();
+    }
+    let flags = probe_flags(path);
+    let cached = CachedFlags {
+        modified,
+        flags: flags.clone(),
+    };
+    // A cache that cannot be written only costs another probe next time.
+    let _ = update_cache(|cache| {
+        cache.tools.insert(key, cached);
+    });
+    flags
+}
+
+/// An unreadable cache, or one written by a newer build, is treated as empty and
+/// rebuilt.
+fn read_cache() -> FlagCache {
+    fs::read_to_string(cache_path())
+        .ok()
+        .and_then(|content| parse_cache(&content).ok())
+        .unwrap_or_default()
+}
+
+fn parse_cache(content: &str) -> Result<FlagCache> {
+    let mut value: Value = serde_json::from_str(content)?;
+    FLAG_CACHE_SCHEMA.upgrade(&mut value)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+fn update_cache(apply: impl FnOnce(&mut FlagCache)) -> Result<()> {
+    let path = cache_path();
+    let _lock = storage::lock_exclusive(&path)?;
+    let mut cache = read_cache();
+    apply(&mut cache);
+    let content = serde_json::to_string_pretty(&cache)?;
+    storage::write_atomic(&path, content.as_bytes()).with_context(|| "failed to write flag cache")
+}
+
+fn cache_path() -> PathBuf {
+    paths::cache_dir().join("flags.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_the_cache() {
+        let cache = parse_cache(r#"{"tools":{"/bin/ls":{"modified":7,"flags":null}}}"#).unwrap();
+        assert_eq!(cache.schema_version, FLAG_CACHE_SCHEMA.current_version());
+        assert_eq!(cache.tools["/bin/ls"].modified, 7);
+        let written = serde_json::to_value(FlagCache::default()).unwrap();
+        assert_eq!(
+            written["schemaVersion"],
+            FLAG_CACHE_SCHEMA.current_version()
+        );
+        assert!(parse_cache(r#"{"schemaVersion":99,"tools":{}}"#).is_err());
+        assert!(parse_cache("not json").is_err());
+    }
+}
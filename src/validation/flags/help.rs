@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+use crate::tools::{self, PROBE_TIMEOUT};
+
+/// Options every getopt-style tool answers to, even when its help does not list them.
+const ALWAYS_ACCEPTED: &[&str] = &["help", "version"];
+
+/// The options a tool documents in its help or man page.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct KnownFlags {
+    /// Letters of the short options, e.g. `"Aal"` for `-A`, `-a` and `-l`.
+    short: String,
+    /// Long options without their dashes.
+    long: Vec<String>,
+    /// Multi-letter options written with a single dash, as in `find -name`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    words: Vec<String>,
+}
+
+impl KnownFlags {
+    /// Collects options from help text: option lists at the start of lines
+    /// (`-a, --all`, busybox's `-1<TAB>...`, man's `-t char`), usage synopses
+    /// (`[-ABCfl]`, `[-A num | -B num]`) and long options anywhere.
+    pub(crate) fn parse(help: &str) -> Self {
+        let mut short = BTreeSet::new();
+        let mut long = BTreeSet::new();
+        let mut words = BTreeSet::new();
+        for line in help.lines() {
+            for token in line.split(|c: char| {
+                c.is_whitespace()
+                    || matches!(c, ',' | '[' | ']' | '|' | '(' | ')' | '\'' | '"' | '`')
+            }) {
+                if let Some(name) = token.strip_prefix("--").map(option_name)
+                    && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+                {
+                    long.insert(name.to_string());
+                }
+            }
+
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('-') {
+                // The option list ends where the description starts.
+                let list = trimmed
+                    .split("  ")
+                    .next()
+                    .and_then(|list| list.split('\t').next())
+                    .unwrap_or_default();
+                for token in list.split(|c: char| c.is_whitespace() || c == ',') {
+                    let Some(rest) = token
+                        .strip_prefix('-')
+                        .filter(|rest| !rest.starts_with('-'))
+                    else {
+                        continue;
+                    };
+                    let name = option_name(rest);
+                    let mut chars = name.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(letter), None) if letter.is_ascii_alphanumeric() => {
+                            short.insert(letter);
+                        }
+                        // `-NUM` and the like are placeholders, not options.
+                        (Some(_), Some(_)) if name.chars().all(|c| c.is_ascii_lowercase()) => {
+                            words.insert(name.to_string());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            let mut rest = line;
+            while let Some(open) = rest.find('[') {
+                let inner = &rest[open + 1..];
+                let close = inner.find(']').unwrap_or(inner.len());
+                for token in inner[..close].split(|c: char| c.is_whitespace() || c == '|') {
+                    if let Some(cluster) = token
+                        .strip_prefix('-')
+                        .filter(|rest| !rest.starts_with('-'))
+                    {
+                        short.extend(
+                            option_name(cluster)
+                                .chars()
+                                .filter(char::is_ascii_alphanumeric),
+                        );
+                    }
+                }
+                rest = &inner[close..];
+            }
+        }
+        KnownFlags {
+            short: short.into_iter().collect(),
+            long: long.into_iter().collect(),
+            words: words.into_iter().collect(),
+        }
+    }
+
+    /// Too few options means the help was not understood, e.g. a one-line error.
+    fn is_usable(&self) -> bool {
+        self.short.chars().count() + self.long.len() >= 3
+    }
+
+    /// Options in `args` that are not documented. Only the first letter of a short option
+    /// cluster is checked, since the rest may be its value (`-n5`, `-d,`).
+    pub(crate) fn unknown_in(&self, args: &[Option<String>]) -> Vec<String> {
+        let mut unknown = Vec::new();
+        for arg in args.iter().flatten() {
+            if arg == "--" {
+                break;
+            }
+            if let Some(long) = arg.strip_prefix("--") {
+                let name = long.split('=').next().unwrap_or_default();
+                // getopt accepts unambiguous abbreviations such as `--ext`.
+                if !name.is_empty()
+                    && !ALWAYS_ACCEPTED.contains(&name)
+                    && !self.long.iter().any(|known| known.starts_with(name))
+                {
+                    unknown.push(format!("--{name}"));
+                }
+            } else if let Some(cluster) = arg.strip_prefix('-') {
+                // `-5` (a count for `head`) and `-` (stdin) are not options.
+                let Some(letter) = cluster.chars().next().filter(char::is_ascii_alphabetic) else {
+                    continue;
+                };
+                if self.words.iter().any(|word| word == cluster)
+                    || (cluster.len() > 1 && !self.words.is_empty())
+                {
+                    continue;
+                }
+                if !self.short.contains(letter) {
+                    unknown.push(format!("-{letter}"));
+                }
+            }
+        }
+        unknown
+    }
+}
+
+/// Reads the options of the binary at `path` from `--help`, falling back to its man page.
+/// BSD tools reject `--help` but still print their usage synopsis, which is enough.
+pub(super) fn probe_flags(path: &Path) -> Option<KnownFlags> {
+    let help = tools::probe(
+        Command::new(path).arg("--help").env("LC_ALL", "C"),
+        PROBE_TIMEOUT,
+    )
+    .map(|output| {
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        text.push('\n');
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        KnownFlags::parse(&text)
+    });
+    if let Some(flags) = help.filter(KnownFlags::is_usable) {
+        return Some(flags);
+    }
+    let name = path.file_name()?;
+    let output = tools::probe(
+        Command::new("man")
+            .arg(name)
+            .env("MANPAGER", "cat")
+            .env("MANWIDTH", "120")
+            .env("LC_ALL", "C"),
+        PROBE_TIMEOUT,
+    )?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(KnownFlags::parse(&strip_overstrike(
+        &String::from_utf8_lossy(&output.stdout),
+    )))
+    .filter(KnownFlags::is_usable)
+}
+
+/// Cuts an option token at its value or punctuation: `sort=WORD` -> `sort`,
+/// `i[SUFFIX]` -> `i`.
+fn option_name(token: &str) -> &str {
+    let end = token
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .unwrap_or(token.len());
+    &token[..end]
+}
+
+/// Removes the `X\bX` bold and `_\bX` underline sequences of formatted man pages.
+fn strip_overstrike(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '\u{8}' {
+            plain.pop();
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<Option<String>> {
+        line.split_whitespace()
+            .map(|arg| Some(arg.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_gnu_help() {
+        let flags = KnownFlags::parse(
+            "Usage: sed [OPTION]... {script-only-if-no-other-script} [input-file]...\n\n  -n, --quiet, --silent\n                 suppress automatic printing of pattern space\n  -e script, --expression=script\n                 add the script to the commands to be executed\n  -i[SUFFIX], --in-place[=SUFFIX]\n                 edit files in place (makes backup if SUFFIX supplied)\n  -E, -r, --regexp-extended\n                 use extended regular expressions in the script\n",
+        );
+        assert_eq!(flags.short, "Eeinr");
+        assert_eq!(
+            flags.long,
+            vec![
+                "expression",
+                "in-place",
+                "quiet",
+                "regexp-extended",
+                "silent"
+            ]
+        );
+        assert!(
+            flags
+                .unknown_in(&args("-E -i.bak --in-place=.bak -n5 s/a/b/ -- -z"))
+                .is_empty()
+        );
+        assert_eq!(
+            flags.unknown_in(&args("-P --null-data --regexp")),
+            vec!["-P", "--null-data"]
+        );
+    }
+
+    #[test]
+    fn parses_bsd_and_busybox_usage() {
+        let bsd = KnownFlags::parse(
+            "grep: unrecognized option `--help'\nusage: grep [-abcdDEFGHhIiJLlMmnOopqRSsUVvwXxZz] [-A num] [-B num] [-C[num]]\n\t[-e pattern] [-f file] [--binary-files=value] [--color=when]\n",
+        );
+        assert!(
+            bsd.unknown_in(&args("-Rn -A 3 --color=auto -e x"))
+                .is_empty()
+        );
+        assert_eq!(
+            bsd.unknown_in(&args("-P --perl-regexp")),
+            vec!["-P", "--perl-regexp"]
+        );
+
+        let busybox = KnownFlags::parse(
+            "Usage: ls [-1AaCxdLHRFplinshrSXvctu] [-w WIDTH] [FILE]...\n\n\t-1\tOne column output\n\t-a\tInclude entries which start with .\n",
+        );
+        assert!(busybox.unknown_in(&args("-la -w 80")).is_empty());
+        assert_eq!(
+            busybox.unknown_in(&args("--sort-by=size")),
+            vec!["--sort-by"]
+        );
+    }
+
+    #[test]
+    fn strips_man_formatting() {
+        assert_eq!(
+            strip_overstrike("-\u{8}--\u{8}-a\u{8}al\u{8}ll\u{8}l"),
+            "--all"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+mod cache;
+mod help;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::parser::collect_invocations;
+use crate::shell::Script;
+
+/// Tools whose `--help` or usage message lists every option they accept and which
+/// differ between GNU, BSD and busybox. Builtins, tools with subcommands, tools with
+/// abridged help and tools that take dash-prefixed operands (`chmod -x`) are left out.
+const CHECKED_TOOLS: &[&str] = &[
+    "ls",
+    "grep",
+    "egrep",
+    "fgrep",
+    "sed",
+    "sort",
+    "uniq",
+    "head",
+    "tail",
+    "cut",
+    "tr",
+    "wc",
+    "du",
+    "df",
+    "stat",
+    "cp",
+    "mv",
+    "rm",
+    "mkdir",
+    "rmdir",
+    "ln",
+    "touch",
+    "readlink",
+    "realpath",
+    "mktemp",
+    "date",
+    "diff",
+    "cat",
+    "tac",
+    "nl",
+    "paste",
+    "join",
+    "comm",
+    "seq",
+    "split",
+    "base64",
+    "md5sum",
+    "sha256sum",
+    "tee",
+    "basename",
+    "dirname",
+];
+
+/// Options the installed tools do not accept, e.g. `grep -P` with BSD grep, as
+/// `<tool> <option>`. Tools whose options could not be determined are not checked.
+pub(crate) fn find_unknown_flags(source: &str, script: &Script) -> Vec<String> {
+    let mut known_by_path = HashMap::<PathBuf, _>::new();
+    let mut unknown = Vec::new();
+    for invocation in collect_invocations(source, script) {
+        let name = Path::new(&invocation.head.name)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if !CHECKED_TOOLS.contains(&name.as_str()) {
+            continue;
+        }
+        let Ok(path) = which::which(&invocation.head.name) else {
+            continue;
+        };
+        let known = known_by_path
+            .entry(path)
+            .or_insert_with_key(|path| cache::known_flags(path));
+        let Some(known) = known else {
+            continue;
+        };
+        for flag in known.unknown_in(&invocation.args) {
+            let entry = format!("{name} {flag}");
+            if !unknown.contains(&entry) {
+                unknown.push(entry);
+            }
+        }
+    }
+    unknown
+}
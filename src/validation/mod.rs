@@ -1,6 +1,11 @@
+mod flags;
 mod parser;
+mod policy;
+mod preview;
 mod report;
+mod risk;
 mod runtime;
+mod sandbox;
 mod shell_checks;
 mod types;
 mod validate;
@@ -10,10 +15,23 @@ mod tests;
 
 use anyhow::Result;
 
-pub use report::ValidationReport;
+pub use policy::PolicyRule;
+pub use report::{FsPreview, ShellcheckFinding, ValidationReport};
+pub use risk::{RiskAssessment, RiskLevel};
 
-pub fn validate_command(command: &str) -> Result<ValidationReport> {
-    validate::validate_command_internal(command)
+/// Checks `command` before it is shown. With `preview_changes`, commands that change
+/// files are also dry-run in the sandbox (see `ValidationReport::fs_preview`).
+pub fn validate_command(
+    command: &str,
+    policy: &[PolicyRule],
+    preview_changes: bool,
+) -> Result<ValidationReport> {
+    validate::validate_command_internal(command, policy, preview_changes)
+}
+
+/// Classifies what `command` can do to the system; see `RiskLevel`.
+pub fn assess_risk(command: &str) -> RiskAssessment {
+    risk::assess_risk(command, crate::shell::parse(command).ok().as_ref())
 }
 
 pub fn normalize_alias_prefixes(shell: &str, command: &str) -> Result<String> {
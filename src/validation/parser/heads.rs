@@ -0,0 +1,358 @@
+use std::path::Path;
+
+use crate::shell::{self, Script, SimpleCommand, Span, Word};
+use crate::validation::types::{CommandHead, Invocation};
+
+/// Heads of every program `script` runs: simple commands at any depth, including those
+/// in `$(...)`, backticks and `<(...)`, plus programs started by `xargs`, `find -exec`
+/// and `sh -c` or `eval` payloads. Heads only known at run time are left out (see
+/// `has_dynamic_heads`).
+pub(crate) fn collect_command_heads(source: &str, script: &Script) -> Vec<CommandHead> {
+    collect_invocations(source, script)
+        .into_iter()
+        .map(|invocation| invocation.head)
+        .collect()
+}
+
+/// Like `collect_command_heads`, with the arguments each program receives.
+pub(crate) fn collect_invocations(source: &str, script: &Script) -> Vec<Invocation> {
+    scan_heads(source, script).into_iter().flatten().collect()
+}
+
+/// Whether some program `script` runs is only known at run time, as in `$cmd -v` or
+/// `sh -c "$script"`.
+pub(crate) fn has_dynamic_heads(source: &str, script: &Script) -> bool {
+    scan_heads(source, script).iter().any(Option::is_none)
+}
+
+#[cfg(test)]
+pub(crate) fn extract_head_command(segment: &str) -> Option<CommandHead> {
+    let script = shell::parse(segment).ok()?;
+    let command = *shell::simple_commands(&script).first()?;
+    locate_head(segment, command)
+}
+
+/// Finds the program a simple command runs, skipping `builtin`/`command` prefixes and
+/// wrappers such as `sudo -u user` or `env`. Returns `None` when there is no head or it
+/// is only known at run time (e.g. `$cmd`).
+pub(crate) fn locate_head(source: &str, command: &SimpleCommand) -> Option<CommandHead> {
+    match find_head(source, &command.words, 0, Scope::default()) {
+        Lookup::Found(head) => Some(head),
+        Lookup::Dynamic | Lookup::Absent => None,
+    }
+}
+
+enum Lookup {
+    Found(CommandHead),
+    /// The program name is computed at run time.
+    Dynamic,
+    /// The words run no program, as in `nohup` alone or `command -v jq`.
+    Absent,
+}
+
+/// How the programs being scanned are started.
+#[derive(Debug, Clone, Copy, Default)]
+struct Scope {
+    launched: bool,
+    elevated: bool,
+}
+
+/// `None` marks a head that is only known at run time.
+fn scan_heads(source: &str, script: &Script) -> Vec<Option<Invocation>> {
+    let mut found = Vec::new();
+    scan_script(source, script, Scope::default(), &mut found);
+    found
+}
+
+fn scan_script(source: &str, script: &Script, scope: Scope, found: &mut Vec<Option<Invocation>>) {
+    shell::for_each_simple_command_deep(script, &mut |command| {
+        scan_words(source, &command.words, 0, scope, found)
+    });
+}
+
+fn scan_words(
+    source: &str,
+    words: &[Word],
+    start: usize,
+    scope: Scope,
+    found: &mut Vec<Option<Invocation>>,
+) {
+    match find_head(source, words, start, scope) {
+        Lookup::Found(head) => {
+            let index = head.word_index;
+            let program = Path::new(&head.name)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let scope = Scope {
+                launched: true,
+                elevated: head.elevated,
+            };
+            let args = words[index + 1..].iter().map(Word::static_value).collect();
+            found.push(Some(Invocation { head, args }));
+            scan_launched(source, &program, words, index + 1, scope, found);
+        }
+        Lookup::Dynamic => found.push(None),
+        Lookup::Absent => {}
+    }
+}
+
+/// Follows programs that `program` itself starts from its arguments `words[start..]`.
+fn scan_launched(
+    source: &str,
+    program: &str,
+    words: &[Word],
+    start: usize,
+    scope: Scope,
+    found: &mut Vec<Option<Invocation>>,
+) {
+    match program {
+        "xargs" => {
+            if let Some(index) = skip_options(words, start, XARGS_VALUE_OPTIONS) {
+                scan_words(source, words, index, scope, found);
+            }
+        }
+        "find" => {
+            let mut index = start;
+            while index < words.len() {
+                let value = words[index].static_value();
+                index += 1;
+                if !matches!(
+                    value.as_deref(),
+                    Some("-exec" | "-execdir" | "-ok" | "-okdir")
+                ) {
+                    continue;
+                }
+                let end = words[index..]
+                    .iter()
+                    .position(|word| matches!(word.static_value().as_deref(), Some(";" | "+")))
+                    .map_or(words.len(), |offset| index + offset);
+                scan_words(source, &words[..end], index, scope, found);
+                index = end;
+            }
+        }
+        "sh" | "bash" | "dash" | "zsh" | "ksh" | "mksh" | "ash" => {
+            let Some(payload) = shell_payload(words, start) else {
+                return;
+            };
+            scan_payload(payload.static_value(), payload.span, scope, found);
+        }
+        // `eval` joins its arguments with spaces and runs the result.
+        "eval" => {
+            let (Some(first), Some(last)) = (words.get(start), words.last()) else {
+                return;
+            };
+            let text = words[start..]
+                .iter()
+                .map(Word::static_value)
+                .collect::<Option<Vec<_>>>()
+                .map(|args| args.join(" "));
+            let span = Span::new(first.span.start, last.span.end);
+            scan_payload(text, span, scope, found);
+        }
+        _ => {}
+    }
+}
+
+/// Scans a command string run by a shell or `eval`; `None` when it is only known at run
+/// time.
+fn scan_payload(
+    text: Option<String>,
+    span: Span,
+    scope: Scope,
+    found: &mut Vec<Option<Invocation>>,
+) {
+    let Some(text) = text else {
+        found.push(None);
+        return;
+    };
+    let Ok(script) = shell::parse(&text) else {
+        found.push(None);
+        return;
+    };
+    let mut nested = Vec::new();
+    scan_script(&text, &script, scope, &mut nested);
+    // Offsets inside the payload do not point into `source`; report the payload.
+    found.extend(nested.into_iter().map(|invocation| {
+        invocation.map(|mut invocation| {
+            invocation.head.span = span;
+            invocation
+        })
+    }));
+}
+
+/// The command string of `sh -c '...'`, given the words after the shell name.
+fn shell_payload(words: &[Word], start: usize) -> Option<&Word> {
+    let mut has_command_option = false;
+    let mut index = start;
+    while let Some(word) = words.get(index) {
+        let Some(value) = word.static_value() else {
+            break;
+        };
+        index += 1;
+        if value == "--" {
+            break;
+        }
+        if !(value.starts_with('-') || value.starts_with('+')) || value.len() == 1 {
+            index -= 1;
+            break;
+        }
+        if matches!(value.as_str(), "-o" | "+o" | "-O" | "+O") {
+            index += 1;
+        } else if !value.starts_with("--") && value.contains('c') {
+            has_command_option = true;
+        }
+    }
+    if has_command_option {
+        words.get(index)
+    } else {
+        None
+    }
+}
+
+fn find_head(source: &str, words: &[Word], start: usize, scope: Scope) -> Lookup {
+    let mut elevated = scope.elevated;
+    let mut wrappers = Vec::new();
+    let mut prefixed_builtin = false;
+    let mut prefixed_command = false;
+    let mut after_env = false;
+    let mut index = start;
+    while index < words.len() {
+        let word = &words[index];
+        let Some(name) = word.static_value() else {
+            return Lookup::Dynamic;
+        };
+        if name.is_empty() || (after_env && looks_like_assignment(&name)) {
+            index += 1;
+            continue;
+        }
+        let lowered = name.to_lowercase();
+        if lowered == "builtin" {
+            prefixed_builtin = true;
+            index += 1;
+            continue;
+        }
+        if lowered == "command" {
+            prefixed_command = true;
+            index += 1;
+            while let Some(option) = words.get(index).and_then(Word::static_value) {
+                match option.as_str() {
+                    "-p" => index += 1,
+                    "--" => {
+                        index += 1;
+                        break;
+                    }
+                    // `command -v name` only looks the name up.
+                    "-v" | "-V" => return Lookup::Absent,
+                    _ => break,
+                }
+            }
+            continue;
+        }
+        let wrapper = WRAPPERS.iter().find(|(wrapper, _, _)| *wrapper == lowered);
+        if let Some((_, value_options, operands)) = wrapper {
+            let next = skip_options(words, index + 1, value_options);
+            // Without a command, `sudo -i` and `doas -s` start a root shell themselves.
+            let runs_shell = next.is_none() && matches!(lowered.as_str(), "sudo" | "doas");
+            if !runs_shell {
+                after_env = lowered == "env";
+                elevated |= matches!(lowered.as_str(), "sudo" | "doas");
+                wrappers.push(lowered);
+                match next {
+                    Some(next) => index = next + operands,
+                    None => return Lookup::Absent,
+                }
+                continue;
+            }
+            elevated = true;
+        }
+        return Lookup::Found(CommandHead {
+            name,
+            prefixed_builtin,
+            prefixed_command,
+            prefixed_backslash: source
+                .get(word.span.start..word.span.end)
+                .is_some_and(|text| text.starts_with('\\')),
+            launched: scope.launched,
+            elevated,
+            wrappers,
+            word_index: index,
+            span: word.span,
+        });
+    }
+    Lookup::Absent
+}
+
+/// Skips the options starting at `words[start]`, including the separate values of
+/// `value_options`, and returns the index of the first operand.
+fn skip_options(words: &[Word], start: usize, value_options: &[&str]) -> Option<usize> {
+    let mut index = start;
+    while let Some(word) = words.get(index) {
+        let Some(value) = word.static_value() else {
+            return Some(index);
+        };
+        if value == "--" {
+            return Some(index + 1);
+        }
+        if !value.starts_with('-') || value == "-" {
+            return Some(index);
+        }
+        index += if value_options.contains(&value.as_str()) {
+            2
+        } else {
+            1
+        };
+    }
+    None
+}
+
+/// Programs that run the rest of their arguments as a command: name, options that take
+/// a separate value, and operands before the command (`timeout 5 cmd`).
+const WRAPPERS: &[(&str, &[&str], usize)] = &[
+    (
+        "sudo",
+        &[
+            "-u", "-g", "-C", "-D", "-h", "-p", "-r", "-t", "-T", "-U", "--user", "--group",
+            "--chdir", "--prompt", "--role", "--type",
+        ],
+        0,
+    ),
+    ("doas", &["-u", "-C"], 0),
+    ("env", &["-u", "-C", "--unset", "--chdir"], 0),
+    ("nohup", &[], 0),
+    ("time", &["-f", "-o", "--format", "--output"], 0),
+    ("nice", &["-n", "--adjustment"], 0),
+    ("timeout", &["-s", "-k", "--signal", "--kill-after"], 1),
+    ("stdbuf", &["-i", "-o", "-e"], 0),
+    ("busybox", &[], 0),
+];
+
+const XARGS_VALUE_OPTIONS: &[&str] = &[
+    "-a",
+    "-d",
+    "-E",
+    "-I",
+    "-L",
+    "-n",
+    "-P",
+    "-s",
+    "--arg-file",
+    "--delimiter",
+    "--max-args",
+    "--max-procs",
+    "--max-chars",
+];
+
+fn looks_like_assignment(token: &str) -> bool {
+    if token.starts_with('-') || !token.contains('=') {
+        return false;
+    }
+    let Some((name, _)) = token.split_once('=') else {
+        return false;
+    };
+    if name.is_empty() {
+        return false;
+    }
+    name.chars()
+        .all(|ch| ch == '_' || ch.is_ascii_alphanumeric())
+}
@@ -1,12 +1,12 @@
-mod cd_checks;
+mod heads;
+mod path_checks;
 mod placeholders;
-mod segments;
-mod tokens;
 
-pub(crate) use cd_checks::find_invalid_cd_directories;
+pub(crate) use heads::{
+    collect_command_heads, collect_invocations, has_dynamic_heads, locate_head,
+};
+pub(crate) use path_checks::{find_invalid_cd_directories, find_missing_paths};
 pub(crate) use placeholders::find_placeholder_tokens;
-pub(crate) use segments::split_segment_ranges;
-pub(crate) use tokens::{collect_command_heads, locate_head_token, tokenize_segment};
 
 #[cfg(test)]
-pub(crate) use tokens::extract_head_command;
+pub(crate) use heads::extract_head_command;
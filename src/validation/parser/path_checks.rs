@@ -0,0 +1,401 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use super::heads::locate_head;
+use crate::shell::{self, RedirectOp, RedirectTarget, Script, Word};
+
+pub(crate) fn find_invalid_cd_directories(source: &str, script: &Script) -> Vec<String> {
+    let mut invalid = BTreeSet::new();
+    let cwd = std::env::current_dir().ok();
+    for command in shell::simple_commands(script) {
+        let Some(head) = locate_head(source, command) else {
+            continue;
+        };
+        if head.name != "cd" || head.elevated {
+            continue;
+        }
+
+        let Some(path_word) = command.words[head.word_index + 1..]
+            .iter()
+            .find(|word| !is_cd_option(word.static_value().as_deref()))
+        else {
+            continue;
+        };
+        let Some(raw_path) = path_word.static_value() else {
+            continue;
+        };
+        if raw_path.is_empty() || raw_path == "-" || contains_dynamic_path(&raw_path) {
+            continue;
+        }
+
+        let resolved = resolve_path(path_word, &raw_path, cwd.as_deref());
+        // A path that cannot be checked, e.g. behind a directory we may not read, counts
+        // as present.
+        let is_invalid = match resolved.try_exists() {
+            Ok(true) => !resolved.is_dir(),
+            Ok(false) => true,
+            Err(_) => false,
+        };
+        if is_invalid {
+            invalid.insert(raw_path.clone());
+        }
+    }
+    invalid.into_iter().collect::<Vec<_>>()
+}
+
+/// How a program's operands map to the files it reads.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Operands {
+    /// Every operand is an input file.
+    Files,
+    /// The first operand is a pattern unless one is given with `-e`/`-f` (`grep`).
+    AfterPattern,
+    /// Only the first operand is read: `source script args`, `uniq input output`.
+    First,
+    /// All operands but the destination, unless `-t DIR` names it (`cp`, `mv`).
+    Sources,
+}
+
+struct Reader {
+    names: &'static [&'static str],
+    /// Options that take the next word as their value.
+    value_options: &'static [&'static str],
+    operands: Operands,
+}
+
+const READERS: &[Reader] = &[
+    Reader {
+        names: &[
+            "cat",
+            "tac",
+            "wc",
+            "md5sum",
+            "sha1sum",
+            "sha256sum",
+            "sha512sum",
+            "comm",
+        ],
+        value_options: &[],
+        operands: Operands::Files,
+    },
+    Reader {
+        names: &["head", "tail"],
+        value_options: &[
+            "-n",
+            "-c",
+            "-s",
+            "--lines",
+            "--bytes",
+            "--pid",
+            "--sleep-interval",
+        ],
+        operands: Operands::Files,
+    },
+    Reader {
+        names: &["sort"],
+        value_options: &[
+            "-k",
+            "-t",
+            "-o",
+            "-S",
+            "-T",
+            "--key",
+            "--field-separator",
+            "--output",
+            "--buffer-size",
+            "--temporary-directory",
+        ],
+        operands: Operands::Files,
+    },
+    Reader {
+        names: &["cut"],
+        value_options: &[
+            "-b",
+            "-c",
+            "-d",
+            "-f",
+            "--bytes",
+            "--characters",
+            "--delimiter",
+            "--fields",
+            "--output-delimiter",
+        ],
+        operands: Operands::Files,
+    },
+    Reader {
+        names: &["nl"],
+        value_options: &["-b", "-d", "-f", "-h", "-i", "-l", "-n", "-s", "-v", "-w"],
+        operands: Operands::Files,
+    },
+    Reader {
+        names: &["diff", "cmp"],
+        value_options: &[
+            "-i",
+            "-n",
+            "-x",
+            "-X",
+            "-I",
+            "-F",
+            "-L",
+            "--label",
+            "--exclude",
+        ],
+        operands: Operands::Files,
+    },
+    Reader {
+        names: &["base64"],
+        value_options: &["-w", "--wrap"],
+        operands: Operands::Files,
+    },
+    Reader {
+        names: &["grep", "egrep", "fgrep"],
+        value_options: &[
+            "-e",
+            "-f",
+            "-m",
+            "-A",
+            "-B",
+            "-C",
+            "-d",
+            "-D",
+            "--regexp",
+            "--file",
+            "--max-count",
+            "--after-context",
+            "--before-context",
+            "--context",
+            "--include",
+            "--exclude",
+            "--exclude-dir",
+            "--label",
+        ],
+        operands: Operands::AfterPattern,
+    },
+    Reader {
+        names: &["source", "."],
+        value_options: &[],
+        operands: Operands::First,
+    },
+    Reader {
+        names: &["uniq"],
+        value_options: &["-f", "-s", "-w"],
+        operands: Operands::First,
+    },
+    Reader {
+        names: &["cp", "mv"],
+        value_options: &["-t", "-S", "--target-directory", "--suffix"],
+        operands: Operands::Sources,
+    },
+];
+
+/// Programs that neither read files named by their operands nor create any, so they
+/// leave later relative inputs checkable.
+const INERT: &[&str] = &[
+    "echo", "printf", "pwd", "ls", "true", "false", "test", "[", "date", "whoami", "uname", "id",
+    "which", "type", "dirname", "basename", "realpath",
+];
+
+/// Input files that do not exist: file operands of common readers (`cat`, `grep`,
+/// `tail -f`, `source`, `cp` sources, ...) and `<` redirections. Globs, expansions and
+/// anything the command itself may create (redirect targets, operands of other
+/// programs) are not reported, nor are relative paths after any other program, which
+/// may download, extract or change directory (`wget URL && head data.csv`). Programs
+/// run through `sudo` or `doas` may read what the user cannot, so they are left alone.
+pub(crate) fn find_missing_paths(source: &str, script: &Script) -> Vec<String> {
+    // Words read as files, and whether a missing one may still be found on `PATH`.
+    let mut inputs = Vec::new();
+    let mut produced = Vec::new();
+    // Where programs start that may create files or change directory.
+    let mut unknown_starts = Vec::new();
+    shell::for_each_simple_command_deep(script, &mut |command| {
+        let Some(head) = locate_head(source, command) else {
+            return;
+        };
+        let words = &command.words[head.word_index + 1..];
+        let name = head
+            .name
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let reader = READERS
+            .iter()
+            .find(|reader| reader.names.contains(&name.as_str()));
+        match reader {
+            Some(reader) if !head.elevated => {
+                let (read, other) = split_operands(reader, words);
+                let searches_path = matches!(name.as_str(), "source" | ".");
+                inputs.extend(read.into_iter().map(|word| (word, searches_path)));
+                produced.extend(other.iter().filter_map(|word| word.static_value()));
+            }
+            _ if INERT.contains(&name.as_str()) && !head.elevated => {}
+            _ => {
+                unknown_starts.push(command.span.start);
+                produced.extend(words.iter().filter_map(Word::static_value));
+            }
+        }
+    });
+    shell::for_each_redirect(script, &mut |redirect| {
+        let RedirectTarget::Word(word) = &redirect.target else {
+            return;
+        };
+        if redirect.op == RedirectOp::Input {
+            inputs.push((word, false));
+        } else if redirect.op.writes() {
+            produced.extend(word.static_value());
+        }
+    });
+
+    let cwd = std::env::current_dir().ok();
+    let mut missing = BTreeSet::new();
+    for (word, searches_path) in inputs {
+        let Some(raw_path) = word.static_value() else {
+            continue;
+        };
+        if raw_path.is_empty()
+            || raw_path == "-"
+            || contains_dynamic_path(&raw_path)
+            || raw_path.chars().all(|c| c.is_ascii_digit())
+            || produced.iter().any(|output| is_within(&raw_path, output))
+        {
+            continue;
+        }
+        if !Path::new(&raw_path).is_absolute()
+            && !word.has_tilde_prefix()
+            && unknown_starts.iter().any(|&start| start < word.span.start)
+        {
+            continue;
+        }
+        let resolved = resolve_path(word, &raw_path, cwd.as_deref());
+        // `source name` also searches `PATH` when the name has no slash.
+        let on_path = searches_path
+            && !raw_path.contains('/')
+            && std::env::var_os("PATH").is_some_and(|path| {
+                std::env::split_paths(&path).any(|dir| dir.join(&raw_path).is_file())
+            });
+        // A path that cannot be checked counts as present.
+        if !resolved.try_exists().unwrap_or(true) && !on_path {
+            missing.insert(raw_path);
+        }
+    }
+    missing.into_iter().collect()
+}
+
+/// Splits a reader's arguments into the words it reads as files and everything else.
+fn split_operands<'a>(reader: &Reader, words: &'a [Word]) -> (Vec<&'a Word>, Vec<&'a Word>) {
+    let mut read = Vec::new();
+    let mut other = Vec::new();
+    let mut operands = Vec::new();
+    let mut pattern_given = false;
+    let mut target_given = false;
+    let mut options_done = false;
+    let mut index = 0;
+    while let Some(word) = words.get(index) {
+        index += 1;
+        let value = word.static_value();
+        let Some(text) = value
+            .as_deref()
+            .filter(|text| !options_done && text.len() > 1 && text.starts_with('-'))
+        else {
+            operands.push(word);
+            continue;
+        };
+        if text == "--" {
+            options_done = true;
+            continue;
+        }
+        let option = text.split('=').next().unwrap_or(text);
+        // In a cluster such as `-rf file`, only the last letter can take the next word.
+        let last = if option.starts_with("--") {
+            option.to_string()
+        } else {
+            format!("-{}", option.chars().last().unwrap_or('-'))
+        };
+        if matches!(last.as_str(), "-e" | "--regexp" | "-f" | "--file") {
+            pattern_given = true;
+        }
+        if matches!(last.as_str(), "-t" | "--target-directory") {
+            target_given = true;
+        }
+        if text.contains('=') || !reader.value_options.contains(&last.as_str()) {
+            continue;
+        }
+        if let Some(value) = words.get(index) {
+            index += 1;
+            let is_pattern_file = reader.operands == Operands::AfterPattern
+                && matches!(last.as_str(), "-f" | "--file");
+            if is_pattern_file {
+                read.push(value);
+            } else {
+                other.push(value);
+            }
+        }
+    }
+
+    match reader.operands {
+        Operands::Files => read.extend(operands),
+        Operands::AfterPattern => {
+            let skip = usize::from(!pattern_given);
+            read.extend(operands.into_iter().skip(skip));
+        }
+        Operands::First => {
+            let mut operands = operands.into_iter();
+            read.extend(operands.next());
+            other.extend(operands);
+        }
+        Operands::Sources if target_given => read.extend(operands),
+        Operands::Sources => {
+            if let Some(destination) = operands.pop()
+                && !operands.is_empty()
+            {
+                read.extend(operands);
+                other.push(destination);
+            }
+        }
+    }
+    (read, other)
+}
+
+/// Whether `path` is `output` or lies inside it, ignoring a leading `./`.
+fn is_within(path: &str, output: &str) -> bool {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    let output = output
+        .strip_prefix("./")
+        .unwrap_or(output)
+        .trim_end_matches('/');
+    !output.is_empty()
+        && (path == output
+            || path
+                .strip_prefix(output)
+                .is_some_and(|rest| rest.starts_with('/')))
+}
+
+fn resolve_path(word: &Word, raw_path: &str, cwd: Option<&Path>) -> PathBuf {
+    let expanded = word
+        .has_tilde_prefix()
+        .then(|| expand_tilde_path(raw_path))
+        .flatten();
+    let candidate = Path::new(expanded.as_deref().unwrap_or(raw_path));
+    match cwd {
+        Some(base) if !candidate.is_absolute() => base.join(candidate),
+        _ => candidate.to_path_buf(),
+    }
+}
+
+fn is_cd_option(word: Option<&str>) -> bool {
+    matches!(word, Some("--" | "-L" | "-P" | "-e" | "-@"))
+}
+
+fn contains_dynamic_path(path: &str) -> bool {
+    path.contains(['*', '?', '[', '{'])
+}
+
+fn expand_tilde_path(path: &str) -> Option<String> {
+    if path == "~" {
+        return dirs::home_dir().map(|home| home.display().to_string());
+    }
+    if let Some(suffix) = path.strip_prefix("~/") {
+        return dirs::home_dir().map(|home| home.join(suffix).display().to_string());
+    }
+    None
+}
@@ -1,4 +1,6 @@
-pub(crate) fn find_placeholder_tokens(command: &str) -> Vec<String> {
+use crate::shell::{self, RedirectOp, RedirectTarget, Script, Word};
+
+pub(crate) fn find_placeholder_tokens(command: &str, script: Option<&Script>) -> Vec<String> {
     let banned_words = ["YOUR_VALUE", "REPLACE_ME", "INSERT_VALUE", "PLACEHOLDER"];
     let mut found = Vec::new();
     let lower = command.to_lowercase();
@@ -8,17 +10,58 @@ pub(crate) fn find_placeholder_tokens(command: &str) -> Vec<String> {
         }
     }
 
-    for token in shell_words::split(command).unwrap_or_default() {
-        if token.len() >= 3
-            && token.starts_with('<')
-            && token.ends_with('>')
-            && !token.contains('/')
-            && !token.contains('\\')
-        {
-            found.push(token);
-        }
+    match script {
+        Some(script) => found.extend(placeholders_in_script(script)),
+        // `<name>` placeholders often make a command unparseable (`cp <src> <dest>`),
+        // so fall back to plain word splitting.
+        None => found.extend(
+            shell_words::split(command)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|token| is_placeholder(token)),
+        ),
     }
     found.sort();
     found.dedup();
     found
 }
+
+fn placeholders_in_script(script: &Script) -> Vec<String> {
+    let mut found = Vec::new();
+    for command in shell::simple_commands(script) {
+        let values = command
+            .words
+            .iter()
+            .chain(
+                command
+                    .assignments
+                    .iter()
+                    .filter_map(|assignment| assignment.value.as_ref()),
+            )
+            .filter_map(Word::static_value);
+        found.extend(values.filter(|value| is_placeholder(value)));
+
+        // `cat <file> out` parses as `< file` followed by `> out`.
+        for pair in command.redirects.windows(2) {
+            let (input, output) = (&pair[0], &pair[1]);
+            if input.op == RedirectOp::Input
+                && output.op == RedirectOp::Output
+                && output.fd.is_none()
+                && input.span.end == output.span.start
+                && let RedirectTarget::Word(target) = &input.target
+                && let Some(name) = target.static_value()
+            {
+                found.push(format!("<{}>", name));
+            }
+        }
+    }
+    found
+}
+
+fn is_placeholder(token: &str) -> bool {
+    token.len() >= 3
+        && token.starts_with('<')
+        && token.ends_with('>')
+        && !token.contains('/')
+        && !token.contains('\\')
+}
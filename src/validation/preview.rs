@@ -0,0 +1,121 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::report::FsPreview;
+use super::runtime::{is_sandbox_safe_head, run_with_timeout};
+use super::sandbox::{RESOURCE_LIMITS, Sandbox};
+use super::types::CommandHead;
+
+const PREVIEW_TIMEOUT_SECONDS: u64 = 5;
+
+/// File tools that only get run for a preview, on top of the ones smoke-tested in the
+/// sandbox anyway.
+const PREVIEW_HEADS: &[&str] = &[
+    "rm", "rmdir", "mv", "cp", "ln", "install", "truncate", "chmod", "patch", "tar", "zip",
+    "unzip", "gzip", "gunzip", "bzip2", "bunzip2", "xz", "unxz",
+];
+
+/// Whether every program in the command is a file tool whose effects the sandbox
+/// contains.
+pub(crate) fn can_preview(heads: &[CommandHead]) -> bool {
+    !heads.is_empty()
+        && heads.iter().all(|head| {
+            PREVIEW_HEADS.contains(&head.name.to_lowercase().as_str())
+                || is_sandbox_safe_head(&head.name)
+        })
+}
+
+/// Runs `command` in `sandbox` over the working directory and lists the paths it
+/// changed there. `None` when the sandbox cannot list changes.
+///
+/// Changes are read from the overlay's upper layer, so a directory that was deleted and
+/// recreated only shows its new contents.
+pub(crate) fn fs_preview(
+    shell: &str,
+    command: &str,
+    sandbox: &Sandbox,
+) -> Result<Option<FsPreview>> {
+    if !sandbox.can_list_changes() {
+        return Ok(None);
+    }
+    let temp_root = std::env::temp_dir().join("command-generator-validation");
+    let id = uuid::Uuid::new_v4();
+    let scratch = temp_root.join(format!("{id}.preview"));
+    fs::create_dir_all(&scratch)?;
+    let file_path = temp_root.join(format!("{id}.sh"));
+    fs::write(&file_path, format!("{RESOURCE_LIMITS}\n{command}\n"))
+        .with_context(|| format!("failed to write preview script: {}", file_path.display()))?;
+
+    let cwd = std::env::current_dir()?;
+    let args = sandbox.command_line(
+        shell,
+        &file_path,
+        &cwd,
+        &scratch,
+        Some(PREVIEW_TIMEOUT_SECONDS),
+    );
+    // The sandbox stops the command itself; the outer limit only covers setup and listing.
+    let preview = run_with_timeout(&args, &cwd, PREVIEW_TIMEOUT_SECONDS + 5).map(|output| {
+        let mut preview = collect_changes(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        preview.note = match output.status.code() {
+            Some(0) => None,
+            Some(124) if stderr.is_empty() => Some(format!(
+                "stopped after {PREVIEW_TIMEOUT_SECONDS}s; the command may change more files"
+            )),
+            code if !stderr.is_empty() => Some(format!(
+                "command failed in the preview with status {code:?}: {}",
+                stderr.lines().take(3).collect::<Vec<_>>().join(" | ")
+            )),
+            _ => None,
+        };
+        preview
+    });
+    let _ = fs::remove_file(&file_path);
+    let _ = fs::remove_dir(&scratch);
+    preview.map(Some)
+}
+
+/// Sorts the upper layer as listed by the sandbox: whiteouts are deleted paths,
+/// anything else is new unless the working directory already had it. Directories that
+/// exist on both sides only hold copied-up parents of changes, so only their contents
+/// are reported.
+fn collect_changes(listing: &[u8]) -> FsPreview {
+    let entries = listing
+        .split(|&byte| byte == 0)
+        .filter_map(|record| {
+            let record = String::from_utf8_lossy(record);
+            let (kinds, path) = record.split_once(' ')?;
+            let mut kinds = kinds.chars();
+            Some((PathBuf::from(path), (kinds.next()?, kinds.next()?)))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut preview = FsPreview::default();
+    let mut descended = HashSet::new();
+    for (path, (kind, original)) in &entries {
+        let parent = path.parent().unwrap_or(Path::new(""));
+        if !parent.as_os_str().is_empty() && !descended.contains(parent) {
+            continue;
+        }
+        let display = |is_dir: bool| {
+            let mut display = path.display().to_string();
+            if is_dir {
+                display.push('/');
+            }
+            display
+        };
+        match (kind, original) {
+            ('w', '-') => {}
+            ('w', original) => preview.deleted.push(display(*original == 'd')),
+            ('d', 'd') => {
+                descended.insert(path.as_path());
+            }
+            (kind, '-') => preview.created.push(display(*kind == 'd')),
+            (kind, _) => preview.modified.push(display(*kind == 'd')),
+        }
+    }
+    preview
+}
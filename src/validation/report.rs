@@ -1,9 +1,16 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::risk::RiskAssessment;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ValidationReport {
     pub syntax_ok: bool,
     pub shell: String,
+    /// Why the validator could not parse a command the shell accepted. The other checks
+    /// all need the parse, so this fails validation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_error: Option<String>,
     #[serde(default)]
     pub missing_binaries: Vec<String>,
     #[serde(default)]
@@ -12,14 +19,72 @@ pub struct ValidationReport {
     pub alias_conflicts: Vec<String>,
     #[serde(default)]
     pub invalid_directories: Vec<String>,
+    /// Input files that do not exist, e.g. `cat` operands or `<` targets.
+    pub missing_paths: Vec<String>,
     #[serde(default)]
     pub placeholder_tokens: Vec<String>,
+    pub policy_violations: Vec<String>,
+    /// Options the installed tools do not document, as `<tool> <option>`.
+    pub unknown_flags: Vec<String>,
+    /// Findings of `shellcheck --severity=warning`; empty when shellcheck is not installed.
+    pub shellcheck_findings: Vec<ShellcheckFinding>,
     #[serde(default)]
     pub runtime_checked: bool,
     #[serde(default = "default_runtime_ok")]
     pub runtime_ok: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub runtime_note: Option<String>,
+    /// `None` in reports saved before risk classification existed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk: Option<RiskAssessment>,
+    /// What a file-modifying command changed when run in the sandbox.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fs_preview: Option<FsPreview>,
+}
+
+/// Paths under the working directory that a command created, modified or deleted during
+/// a sandboxed dry run, relative to that directory. Directories end in `/`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsPreview {
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+    /// Why the lists may be incomplete, e.g. the command failed or timed out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl FsPreview {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+}
+
+/// One shellcheck comment, e.g. `SC2086 (warning, line 1 col 6): Double quote ...`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShellcheckFinding {
+    pub code: u32,
+    /// `error` or `warning`.
+    pub level: String,
+    pub line: u32,
+    pub column: u32,
+    pub message: String,
+}
+
+impl ShellcheckFinding {
+    pub fn is_error(&self) -> bool {
+        self.level == "error"
+    }
+}
+
+impl fmt::Display for ShellcheckFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SC{} ({}, line {} col {}): {}",
+            self.code, self.level, self.line, self.column, self.message
+        )
+    }
 }
 
 fn default_runtime_ok() -> bool {
@@ -29,18 +94,40 @@ fn default_runtime_ok() -> bool {
 impl ValidationReport {
     pub fn is_valid(&self) -> bool {
         self.syntax_ok
+            && self.parse_error.is_none()
             && self.missing_binaries.is_empty()
             && self.alias_conflicts.is_empty()
             && self.invalid_directories.is_empty()
+            && self.missing_paths.is_empty()
             && self.placeholder_tokens.is_empty()
+            && self.policy_violations.is_empty()
+            && self.unknown_flags.is_empty()
+            && !self
+                .shellcheck_findings
+                .iter()
+                .any(ShellcheckFinding::is_error)
             && (!self.runtime_checked || self.runtime_ok)
     }
 
+    /// Non-blocking findings to show the user next to an accepted command.
+    pub fn warnings(&self) -> Vec<String> {
+        self.shellcheck_findings
+            .iter()
+            .filter(|finding| !finding.is_error())
+            .map(ToString::to_string)
+            .collect()
+    }
+
     pub fn to_feedback_text(&self) -> String {
         let mut reasons = Vec::new();
         if !self.syntax_ok {
             reasons.push(format!("shell syntax check failed by {}", self.shell));
         }
+        if let Some(error) = &self.parse_error {
+            reasons.push(format!(
+                "the command could not be analyzed ({error}); use plainer shell syntax"
+            ));
+        }
         if !self.missing_binaries.is_empty() {
             reasons.push(format!(
                 "unresolved commands: {}",
@@ -59,12 +146,42 @@ impl ValidationReport {
                 self.invalid_directories.join(", ")
             ));
         }
+        if !self.missing_paths.is_empty() {
+            reasons.push(format!(
+                "input file not found: {}",
+                self.missing_paths.join(", ")
+            ));
+        }
         if !self.placeholder_tokens.is_empty() {
             reasons.push(format!(
                 "placeholder tokens are not allowed: {}",
                 self.placeholder_tokens.join(", ")
             ));
         }
+        if !self.policy_violations.is_empty() {
+            reasons.push(format!(
+                "policy violations: {}",
+                self.policy_violations.join("; ")
+            ));
+        }
+        if !self.unknown_flags.is_empty() {
+            reasons.push(format!(
+                "options not supported by the installed tools: {} (use only options listed in their --help)",
+                self.unknown_flags.join(", ")
+            ));
+        }
+        let shellcheck_errors = self
+            .shellcheck_findings
+            .iter()
+            .filter(|finding| finding.is_error())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        if !shellcheck_errors.is_empty() {
+            reasons.push(format!(
+                "shellcheck errors: {}",
+                shellcheck_errors.join("; ")
+            ));
+        }
         if self.runtime_checked && !self.runtime_ok {
             if let Some(note) = &self.runtime_note {
                 reasons.push(format!("runtime validation failed: {}", note));
@@ -0,0 +1,546 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+use super::parser::{collect_invocations, has_dynamic_heads};
+use super::policy::runs_downloaded_script;
+use super::types::Invocation;
+use crate::shell::{self, RedirectTarget, Script};
+
+/// How much damage a command can do, from least to most severe.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    #[default]
+    ReadOnly,
+    /// Talks to other hosts.
+    Network,
+    /// Creates or modifies files.
+    WritesFiles,
+    /// Removes files or other resources.
+    Deletes,
+    /// Runs with elevated privileges or changes system state (services, processes, mounts).
+    Privileged,
+    /// Destroys data for good, e.g. `dd`, `mkfs`, `rm -rf /` or `git push --force`.
+    Irreversible,
+}
+
+impl RiskLevel {
+    pub fn label(self) -> &'static str {
+        match self {
+            RiskLevel::ReadOnly => "read-only",
+            RiskLevel::Network => "network",
+            RiskLevel::WritesFiles => "writes files",
+            RiskLevel::Deletes => "deletes",
+            RiskLevel::Privileged => "privileged",
+            RiskLevel::Irreversible => "irreversible",
+        }
+    }
+}
+
+/// The most severe risk a command carries, with one reason per finding.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskAssessment {
+    pub level: RiskLevel,
+    #[serde(default)]
+    pub reasons: Vec<String>,
+}
+
+impl RiskAssessment {
+    /// High-risk commands must be confirmed before they are copied to the clipboard.
+    pub fn is_high(&self) -> bool {
+        self.level >= RiskLevel::Deletes
+    }
+
+    fn add(&mut self, level: RiskLevel, reason: String) {
+        self.level = self.level.max(level);
+        if !self.reasons.contains(&reason) {
+            self.reasons.push(reason);
+        }
+    }
+}
+
+impl fmt::Display for RiskAssessment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.level.label())?;
+        if !self.reasons.is_empty() {
+            write!(f, " ({})", self.reasons.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn assess_risk(source: &str, script: Option<&Script>) -> RiskAssessment {
+    let mut risk = RiskAssessment::default();
+    let Some(script) = script else {
+        risk.add(
+            RiskLevel::WritesFiles,
+            "command could not be parsed, so its effects are unknown".to_string(),
+        );
+        return risk;
+    };
+
+    for invocation in collect_invocations(source, script) {
+        assess_invocation(&invocation, &mut risk);
+    }
+    if has_dynamic_heads(source, script) {
+        risk.add(
+            RiskLevel::WritesFiles,
+            "runs commands only known at run time".to_string(),
+        );
+    }
+    if runs_downloaded_script(source, script) {
+        risk.add(
+            RiskLevel::Irreversible,
+            "runs a downloaded script".to_string(),
+        );
+    }
+    shell::for_each_redirect(script, &mut |redirect| {
+        if !redirect.op.writes() {
+            return;
+        }
+        let RedirectTarget::Word(word) = &redirect.target else {
+            return;
+        };
+        match word.static_value() {
+            Some(target) if is_terminal_device(&target) => {}
+            Some(target) if target.starts_with("/dev/") => risk.add(
+                RiskLevel::Irreversible,
+                format!("writes directly to device {target}"),
+            ),
+            Some(target) => risk.add(
+                RiskLevel::WritesFiles,
+                format!("redirects output into {target}"),
+            ),
+            None => risk.add(
+                RiskLevel::WritesFiles,
+                "redirects output into a file".to_string(),
+            ),
+        }
+    });
+    risk
+}
+
+fn assess_invocation(invocation: &Invocation, risk: &mut RiskAssessment) {
+    let head = &invocation.head;
+    let name = Path::new(&head.name)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let args = &invocation.args;
+    let operands = args
+        .iter()
+        .flatten()
+        .filter(|arg| !arg.starts_with('-'))
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    let subcommand = operands.first().copied().unwrap_or_default();
+
+    if head.elevated {
+        risk.add(
+            RiskLevel::Privileged,
+            format!("{name} runs with elevated privileges"),
+        );
+    }
+    if runs_dynamic_code(&name, args) {
+        risk.add(
+            RiskLevel::Deletes,
+            format!("{name} runs code only known at run time"),
+        );
+    }
+
+    match name.as_str() {
+        "rm" => {
+            let recursive = has_flag(args, 'r', "--recursive") || has_flag(args, 'R', "");
+            match operands.iter().find(|target| is_critical_path(target)) {
+                Some(target) if recursive => risk.add(
+                    RiskLevel::Irreversible,
+                    format!("rm -r {target} deletes a system or home directory"),
+                ),
+                _ if recursive => risk.add(
+                    RiskLevel::Deletes,
+                    "rm -r deletes directory trees".to_string(),
+                ),
+                _ => risk.add(RiskLevel::Deletes, "rm deletes files".to_string()),
+            }
+        }
+        "rmdir" | "unlink" => risk.add(RiskLevel::Deletes, format!("{name} deletes files")),
+        "shred" | "wipefs" => risk.add(
+            RiskLevel::Irreversible,
+            format!("{name} destroys data beyond recovery"),
+        ),
+        "dd" => match args
+            .iter()
+            .flatten()
+            .find_map(|arg| arg.strip_prefix("of="))
+        {
+            Some(target) if target.starts_with("/dev/") && !is_terminal_device(target) => risk.add(
+                RiskLevel::Irreversible,
+                format!("dd writes raw data to device {target}"),
+            ),
+            Some(target) => risk.add(RiskLevel::WritesFiles, format!("dd writes {target}")),
+            None => {}
+        },
+        "fdisk" | "sfdisk" | "parted" | "mkswap" => risk.add(
+            RiskLevel::Irreversible,
+            format!("{name} rewrites disk partitions"),
+        ),
+        _ if name.starts_with("mkfs") => risk.add(
+            RiskLevel::Irreversible,
+            format!("{name} formats a filesystem"),
+        ),
+        "find" if args.iter().flatten().any(|arg| arg == "-delete") => risk.add(
+            RiskLevel::Deletes,
+            "find -delete deletes matches".to_string(),
+        ),
+        "git" => assess_git(subcommand, args, risk),
+        "chmod" | "chown" | "chgrp" => {
+            let recursive = has_flag(args, 'R', "--recursive");
+            match operands.iter().find(|target| is_critical_path(target)) {
+                Some(target) if recursive => risk.add(
+                    RiskLevel::Irreversible,
+                    format!("{name} -R {target} rewrites system-wide ownership or permissions"),
+                ),
+                _ => risk.add(
+                    RiskLevel::WritesFiles,
+                    format!("{name} changes ownership or permissions"),
+                ),
+            }
+        }
+        // `tee` writes every operand; the others write their last one.
+        "tee" if operands.iter().any(|operand| is_block_device(operand)) => risk.add(
+            RiskLevel::Irreversible,
+            "tee overwrites a block device".to_string(),
+        ),
+        "cp" | "mv" | "install" if operands.last().is_some_and(|last| is_block_device(last)) => {
+            risk.add(
+                RiskLevel::Irreversible,
+                format!("{name} overwrites a block device"),
+            )
+        }
+        "cp" | "mv" | "touch" | "mkdir" | "ln" | "install" | "tee" | "truncate" | "patch"
+        | "unzip" | "gunzip" | "gzip" => {
+            risk.add(RiskLevel::WritesFiles, format!("{name} writes files"))
+        }
+        "sed" | "perl" if args.iter().flatten().any(|arg| is_in_place_flag(arg)) => risk.add(
+            RiskLevel::WritesFiles,
+            format!("{name} -i edits files in place"),
+        ),
+        "tar" => {
+            let mode = args
+                .iter()
+                .flatten()
+                .next()
+                .map(String::as_str)
+                .unwrap_or("");
+            if mode.trim_start_matches('-').contains(['x', 'c', 'r', 'u'])
+                || has_flag(args, 'x', "--extract")
+                || has_flag(args, 'c', "--create")
+            {
+                risk.add(RiskLevel::WritesFiles, "tar writes files".to_string());
+            }
+        }
+        "rsync" => {
+            if args.iter().flatten().any(|arg| arg.starts_with("--delete")) {
+                risk.add(
+                    RiskLevel::Deletes,
+                    "rsync --delete removes files missing from the source".to_string(),
+                );
+            } else {
+                risk.add(RiskLevel::WritesFiles, "rsync writes files".to_string());
+            }
+            if operands.iter().any(|operand| is_remote_path(operand)) {
+                risk.add(
+                    RiskLevel::Network,
+                    "rsync copies over the network".to_string(),
+                );
+            }
+        }
+        "scp" => risk.add(
+            RiskLevel::Network,
+            "scp copies over the network".to_string(),
+        ),
+        "curl" => {
+            risk.add(RiskLevel::Network, "curl talks to remote hosts".to_string());
+            if has_flag(args, 'o', "--output") || has_flag(args, 'O', "--remote-name") {
+                risk.add(RiskLevel::WritesFiles, "curl saves downloads".to_string());
+            }
+        }
+        "wget" => risk.add(RiskLevel::WritesFiles, "wget downloads files".to_string()),
+        "ssh" | "sftp" | "ftp" | "telnet" | "nc" | "ncat" | "netcat" => {
+            risk.add(RiskLevel::Network, format!("{name} talks to remote hosts"))
+        }
+        "sudo" | "doas" | "su" | "pkexec" => risk.add(
+            RiskLevel::Privileged,
+            format!("{name} runs commands as another user"),
+        ),
+        "kill" | "pkill" | "killall" => risk.add(
+            RiskLevel::Privileged,
+            format!("{name} terminates processes"),
+        ),
+        "reboot" | "shutdown" | "halt" | "poweroff" => risk.add(
+            RiskLevel::Privileged,
+            format!("{name} restarts or powers off the machine"),
+        ),
+        "mount" | "umount" | "swapon" | "swapoff" | "modprobe" | "rmmod" | "insmod"
+        | "iptables" | "useradd" | "userdel" | "usermod" | "passwd" => risk.add(
+            RiskLevel::Privileged,
+            format!("{name} changes system configuration"),
+        ),
+        "systemctl" | "service" => {
+            let changes = operands.iter().any(|operand| {
+                matches!(
+                    *operand,
+                    "start"
+                        | "stop"
+                        | "restart"
+                        | "reload"
+                        | "enable"
+                        | "disable"
+                        | "mask"
+                        | "kill"
+                )
+            });
+            if changes {
+                risk.add(
+                    RiskLevel::Privileged,
+                    format!("{name} changes running services"),
+                );
+            }
+        }
+        "docker" | "podman" => {
+            if operands
+                .iter()
+                .take(2)
+                .any(|operand| matches!(*operand, "rm" | "rmi" | "prune"))
+            {
+                risk.add(
+                    RiskLevel::Deletes,
+                    format!("{name} removes containers, images or volumes"),
+                );
+            } else if matches!(subcommand, "push" | "pull" | "login") {
+                risk.add(
+                    RiskLevel::Network,
+                    format!("{name} {subcommand} talks to a registry"),
+                );
+            }
+        }
+        "kubectl" => match subcommand {
+            "delete" => risk.add(
+                RiskLevel::Deletes,
+                "kubectl delete removes cluster resources".to_string(),
+            ),
+            "apply" | "create" | "patch" | "replace" | "scale" | "edit" | "rollout" | "drain" => {
+                risk.add(
+                    RiskLevel::WritesFiles,
+                    format!("kubectl {subcommand} changes cluster resources"),
+                )
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+fn assess_git(subcommand: &str, args: &[Option<String>], risk: &mut RiskAssessment) {
+    match subcommand {
+        "push" => {
+            let forced = args
+                .iter()
+                .flatten()
+                .any(|arg| arg == "-f" || arg.starts_with("--force") || arg.starts_with('+'));
+            if forced {
+                risk.add(
+                    RiskLevel::Irreversible,
+                    "git push --force overwrites remote history".to_string(),
+                );
+            } else {
+                risk.add(RiskLevel::Network, "git push updates a remote".to_string());
+            }
+        }
+        "reset" if args.iter().flatten().any(|arg| arg == "--hard") => risk.add(
+            RiskLevel::Irreversible,
+            "git reset --hard discards uncommitted changes".to_string(),
+        ),
+        "clean" if has_flag(args, 'f', "--force") => risk.add(
+            RiskLevel::Irreversible,
+            "git clean -f deletes untracked files".to_string(),
+        ),
+        "rm" => risk.add(RiskLevel::Deletes, "git rm deletes files".to_string()),
+        "clone" | "fetch" | "pull" => {
+            risk.add(
+                RiskLevel::Network,
+                format!("git {subcommand} talks to a remote"),
+            );
+            risk.add(
+                RiskLevel::WritesFiles,
+                format!("git {subcommand} writes the working tree"),
+            );
+        }
+        "add" | "commit" | "checkout" | "switch" | "restore" | "merge" | "rebase" | "stash"
+        | "cherry-pick" | "revert" | "apply" | "am" | "init" | "mv" | "tag" | "reset" => risk.add(
+            RiskLevel::WritesFiles,
+            format!("git {subcommand} changes the repository"),
+        ),
+        _ => {}
+    }
+}
+
+/// Whether `args` contain the short option `short` (also inside `-rf`-style clusters) or
+/// the long option `long`.
+fn has_flag(args: &[Option<String>], short: char, long: &str) -> bool {
+    args.iter()
+        .flatten()
+        .any(|arg| is_short_flag(arg, short) || (!long.is_empty() && arg == long))
+}
+
+fn is_in_place_flag(arg: &str) -> bool {
+    arg.starts_with("--in-place") || is_short_flag(arg, 'i')
+}
+
+fn is_short_flag(arg: &str, short: char) -> bool {
+    arg.len() > 1 && arg.starts_with('-') && !arg.starts_with("--") && arg[1..].contains(short)
+}
+
+/// `eval` or `sh -c` with a command string that is only known at run time. Such code can
+/// do anything, so it counts as high risk.
+fn runs_dynamic_code(name: &str, args: &[Option<String>]) -> bool {
+    match name {
+        "eval" => args.iter().any(Option::is_none),
+        "sh" | "bash" | "dash" | "zsh" | "ksh" | "mksh" | "ash" => args
+            .windows(2)
+            .any(|pair| pair[0].as_deref().is_some_and(is_command_option) && pair[1].is_none()),
+        _ => false,
+    }
+}
+
+fn is_command_option(arg: &str) -> bool {
+    arg.len() > 1 && arg.starts_with('-') && !arg.starts_with("--") && arg.contains('c')
+}
+
+/// Disks and partitions, as opposed to terminals or `/dev/null`.
+fn is_block_device(path: &str) -> bool {
+    [
+        "/dev/sd",
+        "/dev/hd",
+        "/dev/vd",
+        "/dev/xvd",
+        "/dev/nvme",
+        "/dev/mmcblk",
+        "/dev/disk",
+        "/dev/mapper/",
+    ]
+    .iter()
+    .any(|prefix| path.starts_with(prefix))
+}
+
+/// Root, home and top-level system directories, where a recursive change is catastrophic.
+fn is_critical_path(path: &str) -> bool {
+    if !path.starts_with(['/', '~']) {
+        return false;
+    }
+    let trimmed = path.trim_end_matches('*').trim_end_matches('/');
+    matches!(
+        trimmed,
+        "" | "~"
+            | "/bin"
+            | "/boot"
+            | "/dev"
+            | "/etc"
+            | "/home"
+            | "/lib"
+            | "/lib64"
+            | "/opt"
+            | "/root"
+            | "/sbin"
+            | "/srv"
+            | "/sys"
+            | "/usr"
+            | "/var"
+            | "/Applications"
+            | "/System"
+            | "/Users"
+    )
+}
+
+fn is_terminal_device(path: &str) -> bool {
+    matches!(
+        path,
+        "/dev/null" | "/dev/stdout" | "/dev/stderr" | "/dev/tty"
+    ) || path.starts_with("/dev/fd/")
+}
+
+fn is_remote_path(operand: &str) -> bool {
+    operand
+        .split_once(':')
+        .is_some_and(|(host, _)| !host.is_empty() && !host.contains('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assess(command: &str) -> RiskAssessment {
+        assess_risk(command, shell::parse(command).ok().as_ref())
+    }
+
+    #[test]
+    fn classifies_common_commands() {
+        let cases = [
+            ("ls -la | grep src", RiskLevel::ReadOnly),
+            ("echo hi > /dev/null 2>&1", RiskLevel::ReadOnly),
+            ("curl -fsSL https://example.com", RiskLevel::Network),
+            ("echo $(date) >> log.txt", RiskLevel::WritesFiles),
+            ("sed -i 's/a/b/' file.txt", RiskLevel::WritesFiles),
+            ("find . -name '*.log' -mtime +7 -delete", RiskLevel::Deletes),
+            ("ls | xargs rm -f", RiskLevel::Deletes),
+            ("sudo -u app ls /srv", RiskLevel::Privileged),
+            ("sudo -i", RiskLevel::Privileged),
+            ("doas -s", RiskLevel::Privileged),
+            ("systemctl restart nginx", RiskLevel::Privileged),
+            ("git push --force origin main", RiskLevel::Irreversible),
+            (
+                "sudo dd if=image.iso of=/dev/sdb bs=4M",
+                RiskLevel::Irreversible,
+            ),
+            ("sh -c 'rm -rf /'", RiskLevel::Irreversible),
+            ("rm -fr ~/", RiskLevel::Irreversible),
+            ("echo ${x:-$(sudo rm -rf /)}", RiskLevel::Irreversible),
+            ("cat <<EOF\n$(sudo rm -rf /)\nEOF", RiskLevel::Irreversible),
+            ("eval \"rm -rf /\"", RiskLevel::Irreversible),
+            ("eval 'ls -la'", RiskLevel::ReadOnly),
+            ("eval \"$cmd\"", RiskLevel::Deletes),
+            ("bash -c \"$script\"", RiskLevel::Deletes),
+            (
+                "curl -fsSL https://example.com/i.sh | sh",
+                RiskLevel::Irreversible,
+            ),
+            (
+                "bash <(wget -qO- https://example.com/i.sh)",
+                RiskLevel::Irreversible,
+            ),
+            ("busybox rm -rf /", RiskLevel::Irreversible),
+            ("busybox ls", RiskLevel::ReadOnly),
+            ("cp /dev/zero /dev/sda", RiskLevel::Irreversible),
+            ("echo wipe | tee /dev/nvme0n1", RiskLevel::Irreversible),
+            ("cp /dev/sda disk.img", RiskLevel::WritesFiles),
+        ];
+        for (command, level) in cases {
+            assert_eq!(assess(command).level, level, "{command}");
+        }
+    }
+
+    #[test]
+    fn reports_reasons_and_high_risk() {
+        let risk = assess("cd build && rm -rf dist && tar -czf out.tgz src");
+        assert_eq!(risk.level, RiskLevel::Deletes);
+        assert!(risk.is_high());
+        assert_eq!(
+            risk.to_string(),
+            "deletes (rm -r deletes directory trees; tar writes files)"
+        );
+        assert!(!assess("git status && git log -n 3").is_high());
+    }
+}
@@ -3,16 +3,27 @@ use std::fs;
 use std::path::Path;
 use std::process::{Command, Stdio};
 
+use super::risk::{RiskAssessment, RiskLevel};
+use super::sandbox::{RESOURCE_LIMITS, Sandbox};
 use super::types::{CommandHead, RuntimeCheck};
 
-pub(crate) fn runtime_check(shell: &str, command: &str) -> Result<RuntimeCheck> {
+/// Runs `command` with a short timeout. Inside `sandbox` it runs in the real working
+/// directory (behind a copy-on-write overlay); without one it runs in a scratch directory
+/// under the system temp dir.
+pub(crate) fn runtime_check(
+    shell: &str,
+    command: &str,
+    sandbox: Option<&Sandbox>,
+) -> Result<RuntimeCheck> {
     let temp_root = std::env::temp_dir().join("command-generator-validation");
     fs::create_dir_all(&temp_root)?;
-    let mut file_path = temp_root.join(format!("{}.sh", uuid::Uuid::new_v4()));
-    if file_path.extension().is_none() {
-        file_path.set_extension("sh");
-    }
-    fs::write(&file_path, format!("{}\n", command)).with_context(|| {
+    let id = uuid::Uuid::new_v4();
+    let file_path = temp_root.join(format!("{id}.sh"));
+    let script = match sandbox {
+        Some(_) => format!("{RESOURCE_LIMITS}\n{command}\n"),
+        None => format!("{command}\n"),
+    };
+    fs::write(&file_path, script).with_context(|| {
         format!(
             "failed to write runtime validation script: {}",
             file_path.display()
@@ -27,82 +38,112 @@ pub(crate) fn runtime_check(shell: &str, command: &str) -> Result<RuntimeCheck>
         fs::set_permissions(&file_path, perms)?;
     }
 
-    let output = run_with_timeout(shell, &file_path, 2)?;
+    let output = match sandbox {
+        Some(sandbox) => {
+            let scratch = temp_root.join(format!("{id}.d"));
+            fs::create_dir_all(&scratch)?;
+            let cwd = std::env::current_dir()?;
+            let args = sandbox.command_line(shell, &file_path, &cwd, &scratch, None);
+            let output = run_with_timeout(&args, &cwd, RUNTIME_TIMEOUT_SECONDS);
+            let _ = fs::remove_dir(&scratch);
+            output?
+        }
+        None => run_with_timeout(
+            &[shell.to_string(), file_path.display().to_string()],
+            &temp_root,
+            RUNTIME_TIMEOUT_SECONDS,
+        )?,
+    };
     let _ = fs::remove_file(&file_path);
+    let sandbox_suffix = sandbox
+        .map(|sandbox| format!(" (sandbox: {})", sandbox.name()))
+        .unwrap_or_default();
 
     if output.status.success() {
         return Ok(RuntimeCheck {
             ok: true,
-            note: Some("runtime check passed".to_string()),
+            note: Some(format!("runtime check passed{sandbox_suffix}")),
         });
     }
 
     let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-    let mut note = format!("exit status {:?}", output.status.code());
-    if !stderr.is_empty() {
-        let snippet = stderr.lines().take(3).collect::<Vec<_>>().join(" | ");
-        note.push_str(&format!(", stderr: {}", snippet));
+    let code = output.status.code();
+    // `timeout` exits with 124: the command got going without errors, so it passes.
+    if code == Some(124) && stderr.is_empty() {
+        return Ok(RuntimeCheck {
+            ok: true,
+            note: Some(format!(
+                "runtime check timed out after {RUNTIME_TIMEOUT_SECONDS}s without errors{sandbox_suffix}"
+            )),
+        });
+    }
+    // `grep` without matches, `diff` with differences, `test` being false, ...
+    if stderr.is_empty() {
+        return Ok(RuntimeCheck {
+            ok: true,
+            note: Some(format!(
+                "runtime check exited with status {code:?} without error output{sandbox_suffix}"
+            )),
+        });
     }
+    let snippet = stderr.lines().take(3).collect::<Vec<_>>().join(" | ");
     Ok(RuntimeCheck {
         ok: false,
-        note: Some(note),
+        note: Some(format!(
+            "exit status {code:?}, stderr: {snippet}{sandbox_suffix}"
+        )),
     })
 }
 
-fn run_with_timeout(
-    shell: &str,
-    script_path: &Path,
+const RUNTIME_TIMEOUT_SECONDS: u64 = 2;
+
+/// Runs `args` (program first) in `current_dir`, through `timeout`/`gtimeout` when
+/// available.
+pub(super) fn run_with_timeout(
+    args: &[String],
+    current_dir: &Path,
     timeout_seconds: u64,
 ) -> Result<std::process::Output> {
-    let current_dir = script_path
-        .parent()
-        .map(|path| path.to_path_buf())
-        .unwrap_or_else(std::env::temp_dir);
-
-    if let Ok(timeout_bin) = which::which("timeout") {
-        let output = Command::new(timeout_bin)
-            .arg(timeout_seconds.to_string())
-            .arg(shell)
-            .arg(script_path)
-            .current_dir(&current_dir)
-            .stdin(Stdio::null())
-            .output()?;
-        return Ok(output);
-    }
-    if let Ok(timeout_bin) = which::which("gtimeout") {
-        let output = Command::new(timeout_bin)
-            .arg(timeout_seconds.to_string())
-            .arg(shell)
-            .arg(script_path)
-            .current_dir(&current_dir)
-            .stdin(Stdio::null())
-            .output()?;
-        return Ok(output);
-    }
-
-    let output = Command::new(shell)
-        .arg(script_path)
-        .current_dir(&current_dir)
+    let timeout_bin = which::which("timeout").or_else(|_| which::which("gtimeout"));
+    let mut command = match timeout_bin {
+        Ok(timeout_bin) => {
+            let mut command = Command::new(timeout_bin);
+            command.arg(timeout_seconds.to_string()).args(args);
+            command
+        }
+        Err(_) => {
+            let mut command = Command::new(&args[0]);
+            command.args(&args[1..]);
+            command
+        }
+    };
+    let output = command
+        .current_dir(current_dir)
         .stdin(Stdio::null())
         .output()?;
     Ok(output)
 }
 
-pub(crate) fn can_runtime_check(command: &str, heads: &[CommandHead]) -> bool {
+/// Whether `command` is safe to smoke-test: only known read-only programs, plus common
+/// text and file tools when a sandbox contains their effects.
+pub(crate) fn can_runtime_check(command: &str, heads: &[CommandHead], sandboxed: bool) -> bool {
     if command.trim().is_empty() || command.len() > 400 {
         return false;
     }
     let lowered = command.to_lowercase();
-    if lowered.contains("$(")
-        || lowered.contains('`')
-        || lowered.contains(">>")
+    // Substitutions are fine: their commands are part of `heads`. `>(...)` may write.
+    if lowered.contains(">>")
         || lowered.contains("<<")
         || lowered.contains(">|")
-        || lowered.contains("<(")
         || lowered.contains(">(")
     {
         return false;
     }
+    // Commands inside `$((...))` are kept as raw text and never reach `heads`.
+    let has_substitution = lowered.replace("$((", "").contains("$(") || lowered.contains('`');
+    if has_substitution && lowered.contains("$((") {
+        return false;
+    }
 
     let risky_heads = [
         "rm", "mv", "cp", "dd", "mkfs", "reboot", "shutdown", "halt", "poweroff", "kill", "pkill",
@@ -117,13 +158,77 @@ pub(crate) fn can_runtime_check(command: &str, heads: &[CommandHead]) -> bool {
         return false;
     }
 
-    let runtime_safe_heads = [
-        "pwd", "ls", "echo", "print", "printf", "whoami", "uname", "id", "env", "which", "command",
-        "dirname", "basename", "date", "true", "false", "realpath",
-    ];
-
     heads.iter().all(|head| {
         let lowered = head.name.to_lowercase();
-        runtime_safe_heads.iter().any(|safe| lowered == *safe)
+        RUNTIME_SAFE_HEADS.contains(&lowered.as_str())
+            || (sandboxed && SANDBOX_SAFE_HEADS.contains(&lowered.as_str()))
     })
 }
+
+/// Whether the sandbox has room for the command's effects: without an overlay the
+/// working directory is read-only, so only commands that write nothing can pass there.
+pub(crate) fn fits_sandbox(sandbox: Option<&Sandbox>, risk: Option<&RiskAssessment>) -> bool {
+    sandbox.is_none_or(Sandbox::has_overlay)
+        || risk.is_some_and(|risk| risk.level == RiskLevel::ReadOnly)
+}
+
+/// Whether `name` may be smoke-tested inside a sandbox.
+pub(super) fn is_sandbox_safe_head(name: &str) -> bool {
+    let lowered = name.to_lowercase();
+    RUNTIME_SAFE_HEADS.contains(&lowered.as_str()) || SANDBOX_SAFE_HEADS.contains(&lowered.as_str())
+}
+
+const RUNTIME_SAFE_HEADS: &[&str] = &[
+    "pwd", "ls", "echo", "print", "printf", "whoami", "uname", "id", "env", "which", "command",
+    "dirname", "basename", "date", "true", "false", "realpath",
+];
+
+/// Allowed on top of the read-only heads when running sandboxed: reading or rewriting
+/// files is harmless there, but nothing that needs the network or other users.
+const SANDBOX_SAFE_HEADS: &[&str] = &[
+    "grep",
+    "egrep",
+    "fgrep",
+    "rg",
+    "find",
+    "sed",
+    "awk",
+    "gawk",
+    "jq",
+    "yq",
+    "sort",
+    "uniq",
+    "wc",
+    "head",
+    "tail",
+    "cut",
+    "tr",
+    "cat",
+    "tac",
+    "nl",
+    "paste",
+    "join",
+    "column",
+    "fold",
+    "comm",
+    "diff",
+    "cmp",
+    "stat",
+    "file",
+    "du",
+    "df",
+    "tree",
+    "readlink",
+    "xargs",
+    "seq",
+    "test",
+    "[",
+    "expr",
+    "md5sum",
+    "sha256sum",
+    "base64",
+    "xxd",
+    "od",
+    "touch",
+    "mkdir",
+];
@@ -0,0 +1,258 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Isolation for the runtime smoke test: the real filesystem read-only, the working
+/// directory behind a throwaway copy-on-write overlay, and no network.
+#[derive(Debug, Clone)]
+pub(crate) enum Sandbox {
+    /// `bwrap`; `overlay` is false for versions without `--tmp-overlay` (< 0.10), where
+    /// the working directory is bound read-only instead.
+    Bubblewrap { binary: PathBuf, overlay: bool },
+    /// `unshare` with an unprivileged user namespace, set up by `UNSHARE_SETUP`;
+    /// `overlay` is false when the kernel refuses an overlay on the working directory,
+    /// which then stays read-only.
+    Unshare { binary: PathBuf, overlay: bool },
+}
+
+/// Limits applied inside the sandbox before the command runs: CPU seconds, file size
+/// and address space (in KiB).
+pub(crate) const RESOURCE_LIMITS: &str =
+    "ulimit -t 5 2>/dev/null; ulimit -f 20480 2>/dev/null; ulimit -v 4194304 2>/dev/null";
+
+/// Runs as root of the new user and mount namespaces: remounts every filesystem but
+/// `/dev` and `/proc` read-only, giving up if any of them refuses so nothing runs next
+/// to a writable host path (`printf %b` decodes the `\040`-style escapes in mount
+/// points). `/dev` is replaced by a tmpfs holding only `null`, `zero`, `urandom` and
+/// `tty`, and `/proc` by a procfs of the new PID namespace, so neither `/dev/shm` nor
+/// `/proc/<pid>/root` reach the host. Any failed step aborts the setup. Then puts an
+/// overlay on the working directory unless the mode is `readonly` and executes the
+/// script there. The overlay's upper layer lives on a size-limited tmpfs.
+/// Arguments: cwd, scratch directory, shell, script, mode (`overlay`, `list` or
+/// `readonly`) and, for `list`, the script's timeout in seconds.
+///
+/// In `list` mode the script's stdout is dropped and, once it exits, every path in the
+/// upper layer is printed as a NUL-terminated record: its kind there (`w` for a
+/// whiteout, `d` for a directory, `f` for anything else), its kind in the working
+/// directory before the run (`d`, `f` or `-` when absent), a space and the path.
+const UNSHARE_SETUP: &str = r#"set -e
+cwd=$1 scratch=$2 shell=$3 script=$4 mode=$5 limit=$6
+while read -r _ target _; do
+  target=$(printf '%b' "$target")
+  case $target in /dev|/dev/*|/proc|/proc/*) continue ;; esac
+  mount -o remount,bind,ro "$target" || exit 1
+done < /proc/self/mounts
+mount -t tmpfs -o size=64m tmpfs "$scratch"
+mkdir "$scratch/upper" "$scratch/work" "$scratch/tmp" "$scratch/lower" "$scratch/dev"
+mount -t tmpfs -o size=1m,mode=755,nosuid,noexec tmpfs "$scratch/dev"
+for device in null zero urandom tty; do
+  touch "$scratch/dev/$device"
+  mount --bind "/dev/$device" "$scratch/dev/$device"
+done
+mkdir "$scratch/dev/shm"
+ln -s /proc/self/fd "$scratch/dev/fd"
+ln -s fd/0 "$scratch/dev/stdin"
+ln -s fd/1 "$scratch/dev/stdout"
+ln -s fd/2 "$scratch/dev/stderr"
+mount -n --move "$scratch/dev" /dev
+mount -t proc -o nosuid,nodev,noexec proc /proc
+if [ "$mode" = list ]; then
+  mount --bind "$cwd" "$scratch/lower"
+  mount -o remount,bind,ro "$scratch/lower"
+fi
+if [ "$mode" != readonly ]; then
+  mount -t overlay overlay -o "lowerdir=$cwd,upperdir=$scratch/upper,workdir=$scratch/work,userxattr" "$cwd"
+fi
+cd "$cwd"
+TMPDIR=$scratch/tmp
+export TMPDIR
+if [ "$mode" != list ]; then exec "$shell" "$script"; fi
+status=0
+timeout "$limit" "$shell" "$script" >/dev/null || status=$?
+cd "$scratch/upper"
+find . ! -name . -exec sh -c '
+  for path; do
+    if [ -c "$path" ]; then kind=w; elif [ -d "$path" ] && [ ! -L "$path" ]; then kind=d; else kind=f; fi
+    if [ -d "$0/$path" ] && [ ! -L "$0/$path" ]; then was=d
+    elif [ -e "$0/$path" ] || [ -L "$0/$path" ]; then was=f
+    else was=-; fi
+    printf "%s%s %s\0" "$kind" "$was" "${path#./}"
+  done' "$scratch/lower" {} +
+exit "$status"
+"#;
+
+impl Sandbox {
+    /// The first backend that works on this machine, probed once per process. Always
+    /// `None` outside Linux.
+    pub(crate) fn detect() -> Option<&'static Sandbox> {
+        static SANDBOX: OnceLock<Option<Sandbox>> = OnceLock::new();
+        SANDBOX
+            .get_or_init(|| {
+                if !cfg!(target_os = "linux") {
+                    return None;
+                }
+                detect_bubblewrap().or_else(detect_unshare)
+            })
+            .as_ref()
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Sandbox::Bubblewrap { .. } => "bubblewrap",
+            Sandbox::Unshare { .. } => "unshare",
+        }
+    }
+
+    /// Whether the working directory is behind a writable overlay. Without one it is
+    /// read-only, so commands that write files fail there.
+    pub(crate) fn has_overlay(&self) -> bool {
+        match self {
+            Sandbox::Bubblewrap { overlay, .. } | Sandbox::Unshare { overlay, .. } => *overlay,
+        }
+    }
+
+    /// Whether the sandbox can list what a script changed in the working directory; see
+    /// `command_line`. Bubblewrap cannot: its overlays either hide the upper layer or
+    /// keep it in a host directory with no size limit.
+    pub(crate) fn can_list_changes(&self) -> bool {
+        matches!(self, Sandbox::Unshare { overlay: true, .. })
+    }
+
+    /// A sandbox that can list changes, for previews: the detected one if it can,
+    /// otherwise `unshare` when that works with an overlay. Probed once per process by
+    /// running the full setup, so `None` whenever `/dev` and `/proc` cannot be made
+    /// private.
+    pub(crate) fn for_preview() -> Option<&'static Sandbox> {
+        static PREVIEW: OnceLock<Option<Sandbox>> = OnceLock::new();
+        match Self::detect() {
+            Some(sandbox) if sandbox.can_list_changes() => Some(sandbox),
+            Some(Sandbox::Unshare { .. }) | None => None,
+            Some(Sandbox::Bubblewrap { .. }) => PREVIEW
+                .get_or_init(|| detect_unshare().filter(Sandbox::can_list_changes))
+                .as_ref(),
+        }
+    }
+
+    /// Argument list (program first) that runs `script` with `shell` inside the sandbox,
+    /// in `cwd`. `scratch` is an empty directory the sandbox may mount over. With
+    /// `list_timeout`, which needs `can_list_changes`, the script is stopped after that
+    /// many seconds and the changes it made are printed on stdout instead of its own
+    /// output, in the format described at `UNSHARE_SETUP`.
+    pub(crate) fn command_line(
+        &self,
+        shell: &str,
+        script: &Path,
+        cwd: &Path,
+        scratch: &Path,
+        list_timeout: Option<u64>,
+    ) -> Vec<String> {
+        let cwd = cwd.display().to_string();
+        match self {
+            Sandbox::Bubblewrap { binary, overlay } => {
+                let inner_script = "/tmp/.command-generator-validate.sh";
+                let mut args = vec![
+                    binary.display().to_string(),
+                    "--ro-bind".into(),
+                    "/".into(),
+                    "/".into(),
+                    "--dev".into(),
+                    "/dev".into(),
+                    "--proc".into(),
+                    "/proc".into(),
+                    "--tmpfs".into(),
+                    "/tmp".into(),
+                    "--ro-bind".into(),
+                    script.display().to_string(),
+                    inner_script.into(),
+                ];
+                if *overlay {
+                    args.extend(["--overlay-src".into(), cwd.clone()]);
+                    args.extend(["--tmp-overlay".into(), cwd.clone()]);
+                } else {
+                    args.extend(["--ro-bind".into(), cwd.clone(), cwd.clone()]);
+                }
+                args.extend([
+                    "--unshare-all".into(),
+                    "--die-with-parent".into(),
+                    "--new-session".into(),
+                    "--setenv".into(),
+                    "TMPDIR".into(),
+                    "/tmp".into(),
+                    "--chdir".into(),
+                    cwd,
+                    shell.into(),
+                    inner_script.into(),
+                ]);
+                args
+            }
+            Sandbox::Unshare { binary, overlay } => {
+                let mode = match (list_timeout, overlay) {
+                    (Some(_), _) => "list",
+                    (None, true) => "overlay",
+                    (None, false) => "readonly",
+                };
+                vec![
+                    binary.display().to_string(),
+                    "--user".into(),
+                    "--map-root-user".into(),
+                    "--mount".into(),
+                    "--net".into(),
+                    "--pid".into(),
+                    "--ipc".into(),
+                    "--fork".into(),
+                    "--kill-child".into(),
+                    "sh".into(),
+                    "-c".into(),
+                    UNSHARE_SETUP.into(),
+                    "sh".into(),
+                    cwd,
+                    scratch.display().to_string(),
+                    shell.into(),
+                    script.display().to_string(),
+                    mode.into(),
+                    list_timeout.unwrap_or_default().to_string(),
+                ]
+            }
+        }
+    }
+}
+
+fn detect_bubblewrap() -> Option<Sandbox> {
+    let binary = which::which("bwrap").ok()?;
+    if !probe(Command::new(&binary).args(["--ro-bind", "/", "/", "--unshare-all", "true"])) {
+        return None;
+    }
+    let help = Command::new(&binary).arg("--help").output().ok()?;
+    let overlay = String::from_utf8_lossy(&help.stdout).contains("--tmp-overlay");
+    Some(Sandbox::Bubblewrap { binary, overlay })
+}
+
+/// Runs the real setup with an empty script, first with an overlay on the current
+/// directory and then without one.
+fn detect_unshare() -> Option<Sandbox> {
+    let binary = which::which("unshare").ok()?;
+    let cwd = std::env::current_dir().ok()?;
+    let scratch = std::env::temp_dir()
+        .join("command-generator-validation")
+        .join(format!("{}.probe", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&scratch).ok()?;
+    let sandbox = [true, false].into_iter().find_map(|overlay| {
+        let sandbox = Sandbox::Unshare {
+            binary: binary.clone(),
+            overlay,
+        };
+        let args = sandbox.command_line("sh", Path::new("/dev/null"), &cwd, &scratch, None);
+        probe(Command::new(&args[0]).args(&args[1..]).current_dir(&cwd)).then_some(sandbox)
+    });
+    let _ = std::fs::remove_dir_all(&scratch);
+    sandbox
+}
+
+fn probe(command: &mut Command) -> bool {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
@@ -1,66 +1,46 @@
 use anyhow::Result;
+use std::collections::HashMap;
 
 use super::detect::is_alias;
-use crate::validation::parser::{locate_head_token, split_segment_ranges, tokenize_segment};
+use crate::shell;
+use crate::validation::parser::collect_command_heads;
 
 pub(super) fn normalize_alias_prefixes(shell: &str, command: &str) -> Result<String> {
-    let ranges = split_segment_ranges(command);
-    if ranges.is_empty() {
+    let Ok(script) = shell::parse(command) else {
         return Ok(command.to_string());
-    }
+    };
 
-    let mut out = String::new();
-    let mut cursor = 0usize;
-    for (start, end) in ranges {
-        if cursor < start {
-            out.push_str(&command[cursor..start]);
+    let mut alias_cache = HashMap::new();
+    let mut insertions = Vec::new();
+    for head in collect_command_heads(command, &script) {
+        // Only unquoted words as written in the source undergo alias expansion.
+        if head.launched
+            || head.prefixed_builtin
+            || head.prefixed_command
+            || head.prefixed_backslash
+            || command.get(head.span.start..head.span.end) != Some(head.name.as_str())
+        {
+            continue;
         }
-        let raw_segment = &command[start..end];
-        out.push_str(&normalize_alias_prefix_segment(shell, raw_segment)?);
-        cursor = end;
-    }
-    if cursor < command.len() {
-        out.push_str(&command[cursor..]);
-    }
-    Ok(out)
-}
-
-fn normalize_alias_prefix_segment(shell: &str, segment: &str) -> Result<String> {
-    let trimmed = segment.trim();
-    if trimmed.is_empty() {
-        return Ok(segment.to_string());
-    }
-
-    let leading = segment.len() - segment.trim_start().len();
-    let trailing = segment.len() - segment.trim_end().len();
-    let core_end = segment.len().saturating_sub(trailing);
-    let core = &segment[leading..core_end];
-    let tokens = tokenize_segment(core);
-    let Some(head) = locate_head_token(&tokens) else {
-        return Ok(segment.to_string());
-    };
-    if head.prefixed_builtin || head.prefixed_command || head.prefixed_backslash {
-        return Ok(segment.to_string());
-    }
-    if !is_alias(shell, &head.name)? {
-        return Ok(segment.to_string());
+        let aliased = match alias_cache.get(&head.name) {
+            Some(aliased) => *aliased,
+            None => {
+                let aliased = is_alias(shell, &head.name)?;
+                alias_cache.insert(head.name.clone(), aliased);
+                aliased
+            }
+        };
+        if !aliased {
+            continue;
+        }
+        let prefix = if is_known_shell_builtin(&head.name) {
+            "builtin "
+        } else {
+            "\\"
+        };
+        insertions.push((head.span.start, prefix.to_string()));
     }
-
-    let prefix = if is_known_shell_builtin(&head.name) {
-        "builtin "
-    } else {
-        "\\"
-    };
-    let Some(token) = tokens.get(head.token_index) else {
-        return Ok(segment.to_string());
-    };
-    let mut normalized = String::new();
-    normalized.push_str(&segment[..leading]);
-    normalized.push_str(&core[..token.start]);
-    normalized.push_str(prefix);
-    normalized.push_str(&core[token.start..]);
-    normalized.push_str(&segment[core_end..]);
-    Ok(normalized)
+    Ok(shell::apply_insertions(command, insertions))
 }
 
 fn is_known_shell_builtin(name: &str) -> bool {
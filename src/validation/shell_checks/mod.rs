@@ -1,12 +1,19 @@
 mod alias_normalize;
 mod detect;
+mod shellcheck;
 
 use anyhow::Result;
 
+use super::report::ShellcheckFinding;
+
 pub(crate) fn syntax_check(shell: &str, command: &str) -> Result<bool> {
     detect::syntax_check(shell, command)
 }
 
+pub(crate) fn shellcheck(shell: &str, command: &str) -> Result<Option<Vec<ShellcheckFinding>>> {
+    shellcheck::shellcheck(shell, command)
+}
+
 pub(crate) fn command_exists(shell: &str, head: &str) -> Result<bool> {
     detect::command_exists(shell, head)
 }
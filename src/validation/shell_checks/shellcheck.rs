@@ -0,0 +1,108 @@
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::validation::report::ShellcheckFinding;
+
+/// Runs `shellcheck` on `command` in the dialect of `shell`. Returns `None` when
+/// shellcheck is not installed or does not support the shell (e.g. zsh).
+pub(super) fn shellcheck(shell: &str, command: &str) -> Result<Option<Vec<ShellcheckFinding>>> {
+    let Some(dialect) = shellcheck_dialect(shell) else {
+        return Ok(None);
+    };
+    let Ok(binary) = which::which("shellcheck") else {
+        return Ok(None);
+    };
+    let mut child = Command::new(binary)
+        .arg(format!("--shell={dialect}"))
+        .arg("--format=json1")
+        .arg("--severity=warning")
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run shellcheck")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(format!("{command}\n").as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    // 0: no findings, 1: findings; anything else is a usage or internal error.
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("shellcheck failed: {}", stderr.trim()));
+    }
+    parse_json1(&String::from_utf8_lossy(&output.stdout)).map(Some)
+}
+
+fn shellcheck_dialect(shell: &str) -> Option<&'static str> {
+    let name = Path::new(shell)
+        .file_name()?
+        .to_string_lossy()
+        .to_lowercase();
+    match name.as_str() {
+        "sh" | "ash" => Some("sh"),
+        "bash" => Some("bash"),
+        "dash" => Some("dash"),
+        "ksh" | "mksh" => Some("ksh"),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+struct Json1Output {
+    comments: Vec<Json1Comment>,
+}
+
+#[derive(Deserialize)]
+struct Json1Comment {
+    line: u32,
+    column: u32,
+    level: String,
+    code: u32,
+    message: String,
+}
+
+fn parse_json1(raw: &str) -> Result<Vec<ShellcheckFinding>> {
+    let output: Json1Output =
+        serde_json::from_str(raw).context("failed to parse shellcheck output")?;
+    Ok(output
+        .comments
+        .into_iter()
+        .map(|comment| ShellcheckFinding {
+            code: comment.code,
+            level: comment.level,
+            line: comment.line,
+            column: comment.column,
+            message: comment.message,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json1_findings() {
+        let raw = r#"{"comments":[{"file":"-","line":1,"endLine":1,"column":6,"endColumn":10,"level":"warning","code":2086,"message":"Double quote to prevent globbing and word splitting.","fix":null}]}"#;
+        let findings = parse_json1(raw).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].code, 2086);
+        assert!(!findings[0].is_error());
+        assert_eq!(
+            findings[0].to_string(),
+            "SC2086 (warning, line 1 col 6): Double quote to prevent globbing and word splitting."
+        );
+        assert!(parse_json1(r#"{"comments":[]}"#).unwrap().is_empty());
+    }
+
+    #[test]
+    fn maps_shells_to_dialects() {
+        assert_eq!(shellcheck_dialect("/bin/bash"), Some("bash"));
+        assert_eq!(shellcheck_dialect("sh"), Some("sh"));
+        assert_eq!(shellcheck_dialect("/usr/bin/zsh"), None);
+    }
+}
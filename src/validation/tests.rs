@@ -1,44 +1,275 @@
-use super::parser::{collect_command_heads, extract_head_command, find_invalid_cd_directories};
-use super::runtime::can_runtime_check;
+use super::parser::{
+    collect_command_heads, extract_head_command, find_invalid_cd_directories, find_missing_paths,
+    has_dynamic_heads,
+};
+use super::risk::assess_risk;
+use super::runtime::{can_runtime_check, fits_sandbox};
+use super::types::CommandHead;
 use super::*;
+use crate::shell;
+
+fn heads(command: &str) -> Vec<CommandHead> {
+    collect_command_heads(command, &shell::parse(command).unwrap())
+}
 
 #[test]
 fn splits_compound_command() {
-    let heads = collect_command_heads("pwd || true; ls -la | grep src");
+    let heads = heads("pwd || true; ls -la | grep src");
     let names = heads.into_iter().map(|head| head.name).collect::<Vec<_>>();
     assert_eq!(names, vec!["pwd", "true", "ls", "grep"]);
 }
 
 #[test]
 fn handles_no_space_pipeline() {
-    let heads = collect_command_heads("cat Cargo.toml|grep name");
+    let heads = heads("cat Cargo.toml|grep name");
     let names = heads.into_iter().map(|head| head.name).collect::<Vec<_>>();
     assert_eq!(names, vec!["cat", "grep"]);
 }
 
 #[test]
 fn skips_env_assignment() {
-    let heads = collect_command_heads("FOO=bar env ls");
+    let heads = heads("FOO=bar env ls");
     let names = heads.into_iter().map(|head| head.name).collect::<Vec<_>>();
     assert_eq!(names, vec!["ls"]);
 }
 
+#[test]
+fn collects_heads_inside_compound_commands() {
+    let heads =
+        heads("(cd src && make) > log 2>&1 & { ls; } | wc -l; if true; then env A=1 pwd; fi");
+    let names = heads.into_iter().map(|head| head.name).collect::<Vec<_>>();
+    assert_eq!(names, vec!["cd", "make", "ls", "wc", "true", "pwd"]);
+}
+
+#[test]
+fn collects_heads_inside_substitutions() {
+    let heads = heads("echo \"$(jq -r .name package.json)\" `date` && diff <(sort a) b");
+    let names = heads.into_iter().map(|head| head.name).collect::<Vec<_>>();
+    assert_eq!(names, vec!["echo", "jq", "date", "diff", "sort"]);
+}
+
+#[test]
+fn collects_heads_inside_parameter_expansions_and_heredocs() {
+    let names = |command: &str| {
+        heads(command)
+            .into_iter()
+            .map(|head| head.name)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names("echo ${x:-$(sudo rm -rf /)}"), vec!["echo", "rm"]);
+    assert_eq!(names("echo \"${x:-`hostname`}\""), vec!["echo", "hostname"]);
+    assert_eq!(names("cat <<EOF\n$(sudo rm -rf /)\nEOF"), vec!["cat", "rm"]);
+    assert_eq!(names("cat <<'EOF'\n$(rm -rf /)\nEOF"), vec!["cat"]);
+    let command = "cat <<EOF\n${dir:-$(pwd)}\nEOF";
+    assert!(!has_dynamic_heads(command, &shell::parse(command).unwrap()));
+    assert_eq!(names(command), vec!["cat", "pwd"]);
+}
+
+#[test]
+fn collects_heads_started_by_other_programs() {
+    let heads = heads(
+        "find . -name '*.rs' -exec grep -l foo {} \\; | xargs -n 1 wc -l && sudo -u app bash -lc 'cd /srv && make'",
+    );
+    let names = heads
+        .iter()
+        .map(|head| (head.name.as_str(), head.launched))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            ("find", false),
+            ("grep", true),
+            ("xargs", false),
+            ("wc", true),
+            ("bash", false),
+            ("cd", true),
+            ("make", true)
+        ]
+    );
+    let names = self::heads("eval \"nonexistent_tool --x\" '&& ls'")
+        .into_iter()
+        .map(|head| (head.name, head.launched))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            ("eval".to_string(), false),
+            ("nonexistent_tool".to_string(), true),
+            ("ls".to_string(), true)
+        ]
+    );
+}
+
+#[test]
+fn detects_dynamic_heads() {
+    for command in [
+        "$editor notes.txt",
+        "sh -c \"$script\"",
+        "ls | xargs $tool",
+        "eval \"$cmd\"",
+    ] {
+        assert!(
+            has_dynamic_heads(command, &shell::parse(command).unwrap()),
+            "{command}"
+        );
+    }
+    let command = "out=$(pwd); command -v jq && timeout 5 ls";
+    assert!(!has_dynamic_heads(command, &shell::parse(command).unwrap()));
+    let names = heads(command)
+        .into_iter()
+        .map(|head| head.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["pwd", "ls"]);
+}
+
+#[test]
+fn skips_array_assignments_when_finding_heads() {
+    let names = |command| {
+        heads(command)
+            .into_iter()
+            .map(|head| head.name)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names("a[0]=1 ls"), vec!["ls"]);
+    assert_eq!(
+        names("declare -A m=([a]=1); nonexistent_tool_xyz"),
+        vec!["declare", "nonexistent_tool_xyz"]
+    );
+}
+
+#[test]
+fn fails_commands_the_parser_rejects() {
+    let report = validate_command("[[ $a =~ ^(x|y)$ ]]; nonexistent_tool_xyz", &[], false).unwrap();
+    assert!(!report.is_valid());
+    assert!(!report.syntax_ok || report.parse_error.is_some());
+}
+
 #[test]
 fn allows_runtime_for_simple_readonly_command() {
-    let heads = collect_command_heads("pwd");
-    assert!(can_runtime_check("pwd", &heads));
+    let heads = heads("pwd");
+    assert!(can_runtime_check("pwd", &heads, false));
+    let heads = self::heads("echo $(pwd)");
+    assert!(can_runtime_check("echo $(pwd)", &heads, false));
+    let heads = self::heads("echo ${x:-$(pwd)}");
+    assert!(can_runtime_check("echo ${x:-$(pwd)}", &heads, false));
+    let heads = self::heads("echo ${x:-$(rm -rf ~)}");
+    assert!(!can_runtime_check("echo ${x:-$(rm -rf ~)}", &heads, true));
 }
 
 #[test]
 fn skips_runtime_for_risky_command() {
-    let heads = collect_command_heads("rm -rf /tmp/foo");
-    assert!(!can_runtime_check("rm -rf /tmp/foo", &heads));
+    let heads = heads("rm -rf /tmp/foo");
+    assert!(!can_runtime_check("rm -rf /tmp/foo", &heads, true));
+}
+
+#[test]
+fn widens_runtime_allowlist_in_sandbox() {
+    let command = "grep -c name Cargo.toml | sort";
+    let heads = heads(command);
+    assert!(!can_runtime_check(command, &heads, false));
+    assert!(can_runtime_check(command, &heads, true));
+}
+
+#[test]
+fn needs_overlay_for_commands_that_write() {
+    let read_only = sandbox::Sandbox::Unshare {
+        binary: "unshare".into(),
+        overlay: false,
+    };
+    let overlay = sandbox::Sandbox::Unshare {
+        binary: "unshare".into(),
+        overlay: true,
+    };
+    for command in [
+        "touch out",
+        "mkdir -p build",
+        "sed -i s/a/b/ notes.txt",
+        "sort a > b",
+    ] {
+        let risk = assess_risk(command, shell::parse(command).ok().as_ref());
+        assert!(!fits_sandbox(Some(&read_only), Some(&risk)), "{command}");
+        assert!(fits_sandbox(Some(&overlay), Some(&risk)), "{command}");
+        assert!(fits_sandbox(None, Some(&risk)), "{command}");
+    }
+    let risk = assess_risk(
+        "grep -c name Cargo.toml",
+        shell::parse("grep -c name Cargo.toml").ok().as_ref(),
+    );
+    assert!(fits_sandbox(Some(&read_only), Some(&risk)));
+}
+
+#[test]
+fn sandbox_keeps_working_directory_untouched() {
+    let Some(sandbox) = sandbox::Sandbox::detect() else {
+        return;
+    };
+    let marker = format!("sandbox-marker-{}", uuid::Uuid::new_v4());
+    let command = format!("touch {marker} && test -f {marker} && grep -q name Cargo.toml");
+    let runtime = runtime::runtime_check("sh", &command, Some(sandbox)).unwrap();
+    assert!(runtime.ok, "{:?}", runtime.note);
+    assert!(!std::path::Path::new(&marker).exists());
+
+    let runtime = runtime::runtime_check("sh", "cat missing-file-12345", Some(sandbox)).unwrap();
+    assert!(!runtime.ok);
+}
+
+#[test]
+fn sandbox_keeps_dev_and_proc_private() {
+    let Some(sandbox) = sandbox::Sandbox::detect() else {
+        return;
+    };
+    let marker = format!("/dev/shm/sandbox-marker-{}", uuid::Uuid::new_v4());
+    let command = format!(
+        "touch {marker} && test -f {marker} && test ! -e /proc/{}",
+        std::process::id()
+    );
+    let runtime = runtime::runtime_check("sh", &command, Some(sandbox)).unwrap();
+    assert!(runtime.ok, "{:?}", runtime.note);
+    assert!(!std::path::Path::new(&marker).exists());
+}
+
+#[test]
+fn previews_only_when_enabled() {
+    let command = "rm Cargo.lock";
+    let report = validate_command(command, &[], false).unwrap();
+    assert!(report.fs_preview.is_none());
+    if sandbox::Sandbox::for_preview().is_some() {
+        let report = validate_command(command, &[], true).unwrap();
+        assert_eq!(report.fs_preview.unwrap().deleted, vec!["Cargo.lock"]);
+        assert!(std::path::Path::new("Cargo.lock").exists());
+    }
+}
+
+#[test]
+fn previews_file_changes_in_sandbox() {
+    let Some(sandbox) = sandbox::Sandbox::for_preview() else {
+        return;
+    };
+    let marker = format!("preview-marker-{}", uuid::Uuid::new_v4());
+    let command =
+        format!("touch {marker} && rm Cargo.lock && chmod -x Cargo.toml && mkdir -p src/{marker}");
+    let preview = preview::fs_preview("sh", &command, sandbox)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        preview.created,
+        vec![marker.clone(), format!("src/{marker}/")]
+    );
+    assert_eq!(preview.modified, vec!["Cargo.toml"]);
+    assert_eq!(preview.deleted, vec!["Cargo.lock"]);
+    assert_eq!(preview.note, None);
+    assert!(std::path::Path::new("Cargo.lock").exists());
+    assert!(!std::path::Path::new(&marker).exists());
 }
 
 #[test]
 fn detects_placeholder_tokens() {
-    let tokens = parser::find_placeholder_tokens("echo <STRING>");
+    let tokens = parser::find_placeholder_tokens("echo <STRING>", None);
     assert_eq!(tokens, vec!["<STRING>"]);
+    let command = "cat <file> out && echo \"<name>\"";
+    let script = shell::parse(command).unwrap();
+    let tokens = parser::find_placeholder_tokens(command, Some(&script));
+    assert_eq!(tokens, vec!["<file>", "<name>"]);
 }
 
 #[test]
@@ -64,10 +295,59 @@ fn detects_backslash_prefix() {
 
 #[test]
 fn detects_missing_cd_directory() {
-    let invalid = find_invalid_cd_directories("cd ./this_should_not_exist_12345 && pwd");
+    let command = "cd ./this_should_not_exist_12345 && pwd";
+    let invalid = find_invalid_cd_directories(command, &shell::parse(command).unwrap());
     assert_eq!(invalid, vec!["./this_should_not_exist_12345"]);
 }
 
+fn missing_paths(command: &str) -> Vec<String> {
+    find_missing_paths(command, &shell::parse(command).unwrap())
+}
+
+#[test]
+fn detects_missing_input_files() {
+    assert_eq!(
+        missing_paths("cat Cargo.toml missing-1.txt | grep -n name missing-2.txt"),
+        vec!["missing-1.txt", "missing-2.txt"]
+    );
+    assert_eq!(
+        missing_paths("tail -n 20 -f missing.log && sort -u < missing.csv"),
+        vec!["missing.csv", "missing.log"]
+    );
+    assert_eq!(
+        missing_paths("cp -r missing-src src backup && source ./missing.env"),
+        vec!["./missing.env", "missing-src"]
+    );
+    assert_eq!(
+        missing_paths("grep -rf missing-patterns.txt src"),
+        vec!["missing-patterns.txt"]
+    );
+    assert_eq!(
+        missing_paths("echo start && cat missing.txt; wget https://example.com/data.csv"),
+        vec!["missing.txt"]
+    );
+}
+
+#[test]
+fn treats_outputs_globs_and_expansions_as_dynamic() {
+    for command in [
+        "grep -e missing-pattern src Cargo.toml",
+        "cat missing-*.txt \"$HOME/missing\" $(pwd)/missing",
+        "echo hi > out.txt && cat out.txt",
+        "mkdir -p build && cat build/missing.txt",
+        "cd src && cat shell/mod.rs",
+        "sort -o missing-sorted.txt Cargo.toml && head -5 missing-sorted.txt",
+        "cp Cargo.toml missing-copy.toml",
+        "wget https://example.com/data.csv && head missing-data.csv",
+        "curl -O https://example.com/missing.csv && sort < missing.csv",
+        "git clone https://example.com/repo.git && cat missing-repo/README.md",
+        "tar xzf archive.tar.gz && cat missing-extracted/notes.txt",
+        "sudo cat /missing-secret-12345",
+    ] {
+        assert!(missing_paths(command).is_empty(), "{command}");
+    }
+}
+
 #[test]
 fn leaves_prefixed_command_unchanged() {
     let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
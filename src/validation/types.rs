@@ -1,17 +1,30 @@
+use crate::shell::Span;
+
 #[derive(Debug, Clone)]
 pub(crate) struct CommandHead {
     pub(crate) name: String,
     pub(crate) prefixed_builtin: bool,
     pub(crate) prefixed_command: bool,
     pub(crate) prefixed_backslash: bool,
-    pub(crate) token_index: usize,
+    /// Started by another program (`xargs`, `find -exec`, a `sh -c` payload) rather than
+    /// by this shell, so aliases do not apply. For `sh -c` payloads `span` covers the
+    /// payload word and `word_index` refers to the payload's own command.
+    pub(crate) launched: bool,
+    /// Run through `sudo` or `doas`, directly or by an elevated parent program.
+    pub(crate) elevated: bool,
+    /// Wrapper programs skipped before the head, such as `sudo` or `env`.
+    pub(crate) wrappers: Vec<String>,
+    /// Index of the head in the simple command's words.
+    pub(crate) word_index: usize,
+    pub(crate) span: Span,
 }
 
+/// A program the command runs with the arguments after its head; `None` marks words
+/// only known at run time.
 #[derive(Debug, Clone)]
-pub(crate) struct SegmentToken {
-    pub(crate) raw: String,
-    pub(crate) cooked: String,
-    pub(crate) start: usize,
+pub(crate) struct Invocation {
+    pub(crate) head: CommandHead,
+    pub(crate) args: Vec<Option<String>>,
 }
 
 #[derive(Debug)]
@@ -1,12 +1,25 @@
 use anyhow::Result;
 use std::collections::HashSet;
 
-use super::parser::{collect_command_heads, find_invalid_cd_directories, find_placeholder_tokens};
+use super::flags::find_unknown_flags;
+use super::parser::{
+    collect_command_heads, find_invalid_cd_directories, find_missing_paths,
+    find_placeholder_tokens, has_dynamic_heads,
+};
+use super::policy::{PolicyRule, find_policy_violations};
+use super::preview::{can_preview, fs_preview};
 use super::report::ValidationReport;
-use super::runtime::{can_runtime_check, runtime_check};
-use super::shell_checks::{command_exists, is_alias, syntax_check};
+use super::risk::{RiskLevel, assess_risk};
+use super::runtime::{can_runtime_check, fits_sandbox, runtime_check};
+use super::sandbox::Sandbox;
+use super::shell_checks::{command_exists, is_alias, shellcheck, syntax_check};
+use crate::shell;
 
-pub(super) fn validate_command_internal(command: &str) -> Result<ValidationReport> {
+pub(super) fn validate_command_internal(
+    command: &str,
+    policy: &[PolicyRule],
+    preview_changes: bool,
+) -> Result<ValidationReport> {
     let shell = std::env::var("SHELL")
         .ok()
         .map(|value| value.trim().to_string())
@@ -14,12 +27,34 @@ pub(super) fn validate_command_internal(command: &str) -> Result<ValidationRepor
         .unwrap_or_else(|| "sh".to_string());
 
     let syntax_ok = syntax_check(&shell, command)?;
-    let heads = collect_command_heads(command);
+    // shellcheck is optional tooling: if it crashes, validate without it.
+    let shellcheck_findings = if syntax_ok {
+        shellcheck(&shell, command)
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let parsed = shell::parse(command);
+    // When the shell rejects the command too, its syntax error already says why.
+    let parse_error = match &parsed {
+        Err(err) if syntax_ok => Some(err.to_string()),
+        _ => None,
+    };
+    let script = parsed.ok();
+    let heads = script
+        .as_ref()
+        .map(|script| collect_command_heads(command, script))
+        .unwrap_or_default();
     let mut checked = Vec::new();
     let mut missing = Vec::new();
     let mut aliases = Vec::new();
     let mut seen = HashSet::new();
-    let invalid_directories = find_invalid_cd_directories(command);
+    let invalid_directories = script
+        .as_ref()
+        .map(|script| find_invalid_cd_directories(command, script))
+        .unwrap_or_default();
 
     for head in &heads {
         let lowered = head.name.to_lowercase();
@@ -31,7 +66,8 @@ pub(super) fn validate_command_internal(command: &str) -> Result<ValidationRepor
             missing.push(head.name.clone());
             continue;
         }
-        if !head.prefixed_builtin
+        if !head.launched
+            && !head.prefixed_builtin
             && !head.prefixed_command
             && !head.prefixed_backslash
             && is_alias(&shell, &head.name)?
@@ -43,24 +79,46 @@ pub(super) fn validate_command_internal(command: &str) -> Result<ValidationRepor
     let mut report = ValidationReport {
         syntax_ok,
         shell: shell.clone(),
+        parse_error,
         missing_binaries: missing,
         checked_binaries: checked,
         alias_conflicts: aliases,
         invalid_directories,
-        placeholder_tokens: find_placeholder_tokens(command),
+        missing_paths: script
+            .as_ref()
+            .map(|script| find_missing_paths(command, script))
+            .unwrap_or_default(),
+        placeholder_tokens: find_placeholder_tokens(command, script.as_ref()),
+        unknown_flags: script
+            .as_ref()
+            .map(|script| find_unknown_flags(command, script))
+            .unwrap_or_default(),
+        policy_violations: find_policy_violations(command, script.as_ref(), policy),
+        shellcheck_findings,
         runtime_checked: false,
         runtime_ok: true,
         runtime_note: None,
+        risk: Some(assess_risk(command, script.as_ref())),
+        fs_preview: None,
     };
 
-    if report.syntax_ok
-        && report.missing_binaries.is_empty()
-        && report.alias_conflicts.is_empty()
-        && report.invalid_directories.is_empty()
-        && report.placeholder_tokens.is_empty()
-    {
-        if can_runtime_check(command, &heads) {
-            let runtime = runtime_check(&shell, command)?;
+    let sandbox = Sandbox::detect();
+    // Only smoke-test commands that passed every static check.
+    if report.is_valid() {
+        if let Some(script) = &script
+            && has_dynamic_heads(command, script)
+        {
+            report.runtime_note = Some(
+                "runtime check skipped because some commands are only known at run time"
+                    .to_string(),
+            );
+        } else if !fits_sandbox(sandbox, report.risk.as_ref()) {
+            report.runtime_note = Some(
+                "runtime check skipped because the sandbox cannot overlay the working directory"
+                    .to_string(),
+            );
+        } else if can_runtime_check(command, &heads, sandbox.is_some()) {
+            let runtime = runtime_check(&shell, command, sandbox)?;
             report.runtime_checked = true;
             report.runtime_ok = runtime.ok;
             report.runtime_note = runtime.note;
@@ -71,5 +129,21 @@ pub(super) fn validate_command_internal(command: &str) -> Result<ValidationRepor
         }
     }
 
+    // Dry-run commands that change files so the user can see what they would touch.
+    if preview_changes
+        && report.is_valid()
+        && let Some(script) = &script
+        && let Some(sandbox) = Sandbox::for_preview()
+        && report
+            .risk
+            .as_ref()
+            .is_some_and(|risk| matches!(risk.level, RiskLevel::WritesFiles | RiskLevel::Deletes))
+        && !has_dynamic_heads(command, script)
+        && can_preview(&heads)
+    {
+        // The preview is informational: if it cannot run, validate without it.
+        report.fs_preview = fs_preview(&shell, command, sandbox).ok().flatten();
+    }
+
     Ok(report)
 }